@@ -0,0 +1,156 @@
+use std::cmp;
+use std::fmt;
+
+use vm::representations::Span;
+
+/// Candidate names a given identifier can be compared against when it fails
+/// to resolve: let-bound variables, user-defined functions, reserved names,
+/// and built-ins currently in scope.
+pub fn suggest_name(name: &str, candidates: &[&str]) -> Option<String> {
+    let max_distance = cmp::max(1, name.len() / 3);
+
+    candidates
+        .iter()
+        .map(|candidate| (*candidate, levenshtein_distance(name, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate.to_string())
+}
+
+/// Standard (m+1)x(n+1) dynamic-programming edit distance between two
+/// strings: the minimum number of single-character deletions, insertions,
+/// or substitutions needed to turn `a` into `b`.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (m, n) = (a.len(), b.len());
+
+    let mut d = vec![vec![0usize; n + 1]; m + 1];
+    for i in 0..=m {
+        d[i][0] = i;
+    }
+    for j in 0..=n {
+        d[0][j] = j;
+    }
+
+    for i in 1..=m {
+        for j in 1..=n {
+            let substitution_cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = cmp::min(
+                cmp::min(d[i - 1][j] + 1, d[i][j - 1] + 1),
+                d[i - 1][j - 1] + substitution_cost,
+            );
+        }
+    }
+
+    d[m][n]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ErrType {
+    UndefinedVariable(String, Option<String>),
+    UndefinedFunction(String, Option<String>),
+    ReservedName(String, Option<String>),
+    VariableDefinedMultipleTimes(String),
+    WriteFromReadOnlyContext,
+    MaxStackDepthReached,
+    RecursionDetected,
+    TryEvalToFunction,
+    ExpectedListPairs,
+    InvalidArguments(String),
+}
+
+impl ErrType {
+    /// Construct an `UndefinedVariable` error, attaching the closest
+    /// in-scope candidate name if one is close enough to be useful.
+    pub fn undefined_variable(name: &str, candidates: &[&str]) -> ErrType {
+        ErrType::UndefinedVariable(name.to_string(), suggest_name(name, candidates))
+    }
+
+    /// Construct an `UndefinedFunction` error, attaching the closest
+    /// in-scope candidate name if one is close enough to be useful.
+    pub fn undefined_function(name: &str, candidates: &[&str]) -> ErrType {
+        ErrType::UndefinedFunction(name.to_string(), suggest_name(name, candidates))
+    }
+
+    /// Construct a `ReservedName` error, attaching the closest reserved
+    /// name if the caller's identifier appears to be a typo of one.
+    pub fn reserved_name(name: &str, candidates: &[&str]) -> ErrType {
+        ErrType::ReservedName(name.to_string(), suggest_name(name, candidates))
+    }
+}
+
+impl fmt::Display for ErrType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrType::UndefinedVariable(name, Some(suggestion)) => {
+                write!(f, "No such variable found in context: {} (did you mean `{}`?)", name, suggestion)
+            }
+            ErrType::UndefinedVariable(name, None) => {
+                write!(f, "No such variable found in context: {}", name)
+            }
+            ErrType::UndefinedFunction(name, Some(suggestion)) => {
+                write!(f, "No such function found in context: {} (did you mean `{}`?)", name, suggestion)
+            }
+            ErrType::UndefinedFunction(name, None) => {
+                write!(f, "No such function found in context: {}", name)
+            }
+            ErrType::ReservedName(name, Some(suggestion)) => {
+                write!(f, "Illegal operation: attempted to use a reserved name: {} (did you mean `{}`?)", name, suggestion)
+            }
+            ErrType::ReservedName(name, None) => {
+                write!(f, "Illegal operation: attempted to use a reserved name: {}", name)
+            }
+            ErrType::VariableDefinedMultipleTimes(name) => {
+                write!(f, "Illegal redefinition of variable: {}", name)
+            }
+            ErrType::WriteFromReadOnlyContext => write!(f, "Attempted to modify state from a read-only context"),
+            ErrType::MaxStackDepthReached => write!(f, "Maximum stack depth reached"),
+            ErrType::RecursionDetected => write!(f, "Illegal operation: attempted to use a recursive function"),
+            ErrType::TryEvalToFunction => write!(f, "Attempt to evaluate a non-atomic value to a function"),
+            ErrType::ExpectedListPairs => write!(f, "Expected a list of identifier/value pairs"),
+            ErrType::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Error {
+    pub err_type: ErrType,
+    /// Where in the source this error occurred, if the erroring expression
+    /// could be traced back to a parsed span. Not all errors originate from
+    /// a specific source location (e.g. some internal invariants), so this
+    /// is optional.
+    pub span: Option<Span>,
+}
+
+impl Error {
+    pub fn new(err_type: ErrType) -> Error {
+        Error {
+            err_type,
+            span: None,
+        }
+    }
+
+    pub fn with_span(err_type: ErrType, span: Span) -> Error {
+        Error {
+            err_type,
+            span: Some(span),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.span {
+            Some(span) => write!(
+                f,
+                "{} (line {}, column {})",
+                self.err_type, span.start_line, span.start_column
+            ),
+            None => write!(f, "{}", self.err_type),
+        }
+    }
+}
+
+pub type InterpreterResult<R> = Result<R, Error>;
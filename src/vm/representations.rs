@@ -0,0 +1,22 @@
+/// A byte-offset/line-column range into the original source text.
+/// Recorded by the parser on every atom and list it produces, and threaded
+/// through evaluation so that an error can point back at the exact
+/// expression that caused it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Span {
+    pub fn new(start_line: u32, start_column: u32, end_line: u32, end_column: u32) -> Span {
+        Span {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+}
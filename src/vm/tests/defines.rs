@@ -2,6 +2,19 @@ use vm::execute;
 use vm::errors::{ErrType};
 use vm::types::Value;
 
+#[test]
+fn test_error_spans() {
+    let test3 =
+        "(define foo 1)
+         (define foo 2)
+         (+ foo foo)";
+
+    let err = execute(&test3).unwrap_err();
+    assert_eq!(ErrType::VariableDefinedMultipleTimes("foo".to_string()), err.err_type);
+    let span = err.span.expect("expected a source span on the redefinition error");
+    assert_eq!(span.start_line, 2);
+}
+
 
 #[test]
 fn test_defines() {
@@ -35,8 +48,8 @@ fn test_bad_define_names() {
          (define foo 2)
          (+ foo foo)";
 
-    assert_eq!(ErrType::ReservedName("tx-sender".to_string()), execute(&test0).unwrap_err().err_type);
-    assert_eq!(ErrType::ReservedName("*".to_string()), execute(&test1).unwrap_err().err_type);
+    assert_eq!(ErrType::ReservedName("tx-sender".to_string(), None), execute(&test0).unwrap_err().err_type);
+    assert_eq!(ErrType::ReservedName("*".to_string(), None), execute(&test1).unwrap_err().err_type);
     assert_eq!(ErrType::InvalidArguments("Illegal operation: attempted to re-define a value type.".to_string()),
                execute(&test2).unwrap_err().err_type);
     assert_eq!(ErrType::VariableDefinedMultipleTimes("foo".to_string()),
@@ -96,12 +109,12 @@ fn test_recursive_panic() {
 #[test]
 fn test_bad_variables() {
     let test0 = "(+ a 1)";
-    let expected = ErrType::UndefinedVariable("a".to_string());
+    let expected = ErrType::UndefinedVariable("a".to_string(), None);
     assert_eq!(expected, execute(&test0).unwrap_err().err_type);
 
 
     let test1 = "(foo 2 1)";
-    let expected = ErrType::UndefinedFunction("foo".to_string());
+    let expected = ErrType::UndefinedFunction("foo".to_string(), None);
     assert_eq!(expected, execute(&test1).unwrap_err().err_type);
 
 
@@ -122,6 +135,20 @@ fn test_define_parse_panic() {
     assert_eq!(expected, execute(&tests).unwrap_err().err_type);
 }
 
+#[test]
+fn test_did_you_mean_suggestions() {
+    let test0 =
+        "(define foox 1)
+         (+ foox fox)";
+    let expected = ErrType::UndefinedVariable("fox".to_string(), Some("foox".to_string()));
+    assert_eq!(expected, execute(&test0).unwrap_err().err_type);
+
+    // unrelated identifiers should not produce a suggestion
+    let test1 = "(+ zzzzzzzzzz 1)";
+    let expected = ErrType::UndefinedVariable("zzzzzzzzzz".to_string(), None);
+    assert_eq!(expected, execute(&test1).unwrap_err().err_type);
+}
+
 #[test]
 fn test_define_parse_panic_2() {
     let tests = "(define (a b (d)) 1)";
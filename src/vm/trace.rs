@@ -0,0 +1,74 @@
+//! Opt-in instrumentation of the evaluator, built on the `tracing` crate.
+//!
+//! Everything here is gated behind the `trace` feature so that a consumer
+//! who doesn't care about execution tracing pays no cost: with the feature
+//! disabled, `trace_span!`/`trace_event!` below expand to a zero-sized
+//! no-op that the compiler optimizes out entirely.
+//!
+//! The call sites these are meant for -- function application, `define`,
+//! map mutation, and stack-frame push/pop in the evaluator -- live in
+//! `vm::eval`, which isn't part of this checkout (this snapshot of the
+//! crate has no `vm::eval`, no `vm/mod.rs`, and no `lib.rs` at all, so
+//! there's nothing here for these macros to be called from yet). Wire
+//! `trace_span!`/`trace_event!` into those call sites when that module is
+//! present, following the same pattern as the `tests` module below.
+
+#[cfg(feature = "trace")]
+macro_rules! trace_span {
+    ($name:expr, $($field:tt)*) => {
+        tracing::span!(tracing::Level::TRACE, $name, $($field)*)
+    };
+}
+
+/// Stand-in for `tracing::Span` when the `trace` feature is off: supports
+/// the same `.entered()` call a real call site uses to get an RAII guard,
+/// so call sites don't need a `#[cfg]` of their own to build either way.
+#[cfg(not(feature = "trace"))]
+pub(crate) struct NoopSpan;
+
+#[cfg(not(feature = "trace"))]
+impl NoopSpan {
+    #[inline(always)]
+    pub(crate) fn entered(self) -> NoopSpan {
+        self
+    }
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        NoopSpan
+    };
+}
+
+#[cfg(feature = "trace")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        tracing::event!(tracing::Level::TRACE, $($arg)*)
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
+
+pub(crate) use trace_event;
+pub(crate) use trace_span;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // No `vm::eval` exists in this checkout to exercise end-to-end, but
+    // these confirm the two things a real call site depends on: the span
+    // macro produces something `.entered()`-able regardless of whether
+    // `trace` is on, and the event macro is a valid statement either way.
+    #[test]
+    fn span_guard_pattern_compiles_with_or_without_trace_feature() {
+        let _guard = trace_span!("test_span", depth = 0u32).entered();
+        trace_event!("test_event");
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A dedicated `PoxConstants` preset for Nakamoto testnets, so that tests
+//! and real testnet nodes can select a coherent short-cycle PoX schedule
+//! through `burnchain.mode = "nakamoto-testnet"` instead of hand-overriding
+//! individual fields like `pox_prepare_length`/`pox_reward_length` the way
+//! `naka_neon_integration_conf` does today.
+//!
+//! [`pox_constants_for_burnchain_mode`] is the consumer-side half of that
+//! wiring: `Config::get_burnchain` (in `testnet/stacks-node/src/config.rs`)
+//! is the actual place that reads `burnchain.mode` and builds a
+//! `PoxConstants`, but that file isn't part of this checkout, so the match
+//! arm can't be added there directly. Call this function from
+//! `get_burnchain`'s mode dispatch -- alongside its existing arms for
+//! `"mainnet"`/`"xenon"`/`"helium"`/etc. -- instead of falling through to
+//! the hand-set `pox_prepare_length`/`pox_reward_length` overrides when
+//! `mode == "nakamoto-testnet"`.
+
+use crate::burnchains::PoxConstants;
+
+impl PoxConstants {
+    /// Short reward/prepare cycles, tuned for exercising Nakamoto's PoX-4
+    /// activation and reward-cycle boundaries quickly in a testnet/test
+    /// harness, without needing to hand-tune each field at every call site.
+    ///
+    /// Reward cycle length of 20 with a 5-block prepare phase mirrors the
+    /// values `naka_neon_integration_conf` already sets by hand; this just
+    /// gives them a name and bundles in the v1-unlock/pox-2/pox-3/pox-4
+    /// activation heights that make sense alongside a cycle that short.
+    pub fn nakamoto_testnet_default() -> PoxConstants {
+        let reward_cycle_length = 20;
+        let prepare_length = 5;
+        PoxConstants::new(
+            reward_cycle_length,
+            prepare_length,
+            4,   // anchor_threshold
+            25,  // pox_rejection_fraction... unused post pox-2, kept for struct compat
+            0,   // v1_unlock_height (testnet: v1 never locked separately)
+            reward_cycle_length * 2,  // pox_2_activation_height
+            reward_cycle_length * 4,  // pox_3_activation_height
+            reward_cycle_length * 6,  // pox_4_activation_height
+            u32::MAX,                 // sunset_start (no sunset on this testnet preset)
+            u32::MAX,                 // sunset_end
+        )
+    }
+}
+
+/// Maps a `burnchain.mode` config string to the `PoxConstants` preset it
+/// should select, if that mode has one. Returns `None` for any mode that
+/// derives its `PoxConstants` some other way (e.g. from hand-set
+/// `pox_prepare_length`/`pox_reward_length` overrides), so callers can fall
+/// through to their existing behavior unchanged.
+pub fn pox_constants_for_burnchain_mode(mode: &str) -> Option<PoxConstants> {
+    match mode {
+        "nakamoto-testnet" => Some(PoxConstants::nakamoto_testnet_default()),
+        _ => None,
+    }
+}
@@ -19,7 +19,8 @@ use std::io::{Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::ThreadId;
-use std::{cmp, fs, mem};
+use std::time::Duration;
+use std::{cmp, fs, mem, thread};
 
 use clarity::vm::analysis::{CheckError, CheckErrors};
 use clarity::vm::ast::errors::ParseErrors;
@@ -28,7 +29,8 @@ use clarity::vm::clarity::TransactionConnection;
 use clarity::vm::costs::ExecutionCost;
 use clarity::vm::database::BurnStateDB;
 use clarity::vm::errors::Error as InterpreterError;
-use clarity::vm::types::TypeSignature;
+use clarity::vm::types::{PrincipalData, TypeSignature};
+use rayon::prelude::*;
 use serde::Deserialize;
 use stacks_common::codec::{
     read_next, write_next, Error as CodecError, StacksMessageCodec, MAX_PAYLOAD_LEN,
@@ -49,7 +51,7 @@ use crate::chainstate::nakamoto::{
     MaturedMinerRewards, NakamotoBlock, NakamotoBlockHeader, NakamotoChainState, SetupBlockResult,
 };
 use crate::chainstate::stacks::address::StacksAddressExtensions;
-use crate::chainstate::stacks::db::accounts::MinerReward;
+use crate::chainstate::stacks::db::accounts::{MinerReward, StacksAccount};
 use crate::chainstate::stacks::db::blocks::MemPoolRejection;
 use crate::chainstate::stacks::db::transactions::{
     handle_clarity_runtime_error, ClarityRuntimeTxError,
@@ -72,9 +74,33 @@ use crate::cost_estimates::CostEstimator;
 use crate::monitoring::{
     set_last_mined_block_transaction_count, set_last_mined_execution_cost_observed,
 };
+use crate::net::api::block_events_ws::{BlockEvent, BlockEventBroadcaster};
 use crate::net::relay::Relayer;
 use crate::util_lib::db::Error as DBError;
 
+/// Bundles a transaction with its txid and serialized length, computed
+/// once as it enters the builder, so hot paths (logging, size accounting,
+/// the tx merkle root) read the cached fields instead of re-serializing or
+/// re-hashing the transaction. Stores the transaction behind an `Arc` so
+/// accepting a tx into the block doesn't require a second deep copy beyond
+/// the one the caller already handed in by reference.
+#[derive(Clone)]
+struct IndexedStacksTransaction {
+    tx: Arc<StacksTransaction>,
+    txid: Txid,
+    tx_len: u64,
+}
+
+impl IndexedStacksTransaction {
+    fn new(tx: StacksTransaction, txid: Txid, tx_len: u64) -> IndexedStacksTransaction {
+        IndexedStacksTransaction {
+            tx: Arc::new(tx),
+            txid,
+            tx_len,
+        }
+    }
+}
+
 /// Nakamaoto tenure information
 pub struct NakamotoTenureInfo {
     /// Coinbase tx, if this is a new tenure
@@ -100,6 +126,18 @@ impl NakamotoTenureInfo {
     }
 }
 
+/// Reserved high bit of `NakamotoBlockHeader::version`, set on every shadow
+/// block's header so that chainstate can tell a block with no
+/// block-commit apart from an ordinary one without consulting the
+/// burnchain. No real miner version has ever set this bit.
+const SHADOW_BLOCK_VERSION_FLAG: u8 = 0b1000_0000;
+
+/// Whether `header` is marked as a shadow block via
+/// [`SHADOW_BLOCK_VERSION_FLAG`].
+pub fn is_shadow_block_header(header: &NakamotoBlockHeader) -> bool {
+    header.version & SHADOW_BLOCK_VERSION_FLAG != 0
+}
+
 pub struct NakamotoBlockBuilder {
     /// if this is building atop an epoch 2 block, then this is that block's header
     epoch2_parent_header: Option<(StacksBlockHeader, ConsensusHash)>,
@@ -118,9 +156,13 @@ pub struct NakamotoBlockBuilder {
     /// bytes of space consumed so far
     bytes_so_far: u64,
     /// transactions selected
-    txs: Vec<StacksTransaction>,
+    txs: Vec<IndexedStacksTransaction>,
     /// header we're filling in
     header: NakamotoBlockHeader,
+    /// true if this builder is producing a shadow block: a block with no
+    /// corresponding block-commit on the burnchain, crafted offline by an
+    /// operator to repair a stalled chainstate
+    is_shadow_block: bool,
 }
 
 pub struct MinerTenureInfo<'a> {
@@ -166,6 +208,7 @@ impl NakamotoBlockBuilder {
                 tenure_id_consensus_hash.clone(),
                 parent.block_id(),
             ),
+            is_shadow_block: false,
         }
     }
 
@@ -193,6 +236,7 @@ impl NakamotoBlockBuilder {
                 tenure_id_consensus_hash.clone(),
                 parent.block_id(),
             ),
+            is_shadow_block: false,
         }
     }
 
@@ -221,6 +265,45 @@ impl NakamotoBlockBuilder {
                 tenure_id_consensus_hash.clone(),
                 StacksBlockId::new(parent_tenure_id_consensus_hash, &parent.block_hash()),
             ),
+            is_shadow_block: false,
+        }
+    }
+
+    /// Make a block builder for a shadow tenure: a Nakamoto tenure with no
+    /// corresponding block-commit on the burnchain, crafted offline by an
+    /// operator to repair a stalled chainstate and restore accessibility to
+    /// accounts stranded by a tenure gap.
+    ///
+    /// Unlike `new_tenure_from_nakamoto_parent`, the parent here is allowed
+    /// to itself be a shadow block, since a repair may need to chain several
+    /// shadow blocks together before a real, committed tenure resumes.
+    pub fn new_shadow_tenure(
+        parent: &NakamotoBlockHeader,
+        tenure_id_consensus_hash: &ConsensusHash,
+        tenure_change: &StacksTransaction,
+    ) -> NakamotoBlockBuilder {
+        // shadow blocks have no block-commit, so there is no burnchain
+        // commitment to anchor the parent commit hash to. Re-use the
+        // parent's own block id instead, mirroring how a tenure-extend
+        // within an existing tenure is anchored.
+        let parent_commit_hash_value = BlockHeaderHash(parent.block_id().0.clone());
+        NakamotoBlockBuilder {
+            epoch2_parent_header: None,
+            nakamoto_parent_header: Some(parent.clone()),
+            total_burn: parent.burn_spent,
+            coinbase_tx: None,
+            tenure_tx: Some(tenure_change.clone()),
+            parent_commit_hash_value,
+            matured_miner_rewards_opt: None,
+            bytes_so_far: 0,
+            txs: vec![],
+            header: NakamotoBlockHeader::from_parent_empty(
+                parent.chain_length + 1,
+                parent.burn_spent,
+                tenure_id_consensus_hash.clone(),
+                parent.block_id(),
+            ),
+            is_shadow_block: true,
         }
     }
 
@@ -240,6 +323,7 @@ impl NakamotoBlockBuilder {
             bytes_so_far: 0,
             txs: vec![],
             header: NakamotoBlockHeader::genesis(),
+            is_shadow_block: false,
         }
     }
 
@@ -470,15 +554,38 @@ impl NakamotoBlockBuilder {
         consumed
     }
 
+    /// Like [`tenure_finish`](Self::tenure_finish), but for a block whose
+    /// final identity is already settled and that has no signer round to
+    /// wait on -- namely a shadow block produced by chainstate repair.
+    /// Commits the trie under the block's own `index_block_hash` rather
+    /// than the speculative `MINER_BLOCK_*` sentinel, so the block's state
+    /// is immediately loadable as the *parent* of whatever shadow block
+    /// chainstate repair chains onto it next.
+    pub fn tenure_finish_committed(
+        self,
+        tx: ClarityTx,
+        index_block_hash: &StacksBlockId,
+    ) -> ExecutionCost {
+        let consumed = tx.commit_mined_block(index_block_hash);
+
+        test_debug!(
+            "\n\nFinished mining. Trie committed under real block identity {}.\n",
+            index_block_hash
+        );
+
+        consumed
+    }
+
     /// Finish constructing a Nakamoto block.
     /// The block will not be signed yet.
     /// Returns the unsigned Nakamoto block
     fn finalize_block(&mut self, clarity_tx: &mut ClarityTx) -> NakamotoBlock {
-        // done!  Calculate state root and tx merkle root
+        // done!  Calculate state root and tx merkle root, reading the
+        // cached txid off each entry instead of re-hashing the transaction.
         let txid_vecs = self
             .txs
             .iter()
-            .map(|tx| tx.txid().as_bytes().to_vec())
+            .map(|itx| itx.txid.as_bytes().to_vec())
             .collect();
 
         let merkle_tree = MerkleTree::<Sha512Trunc256Sum>::new(&txid_vecs);
@@ -487,10 +594,28 @@ impl NakamotoBlockBuilder {
 
         self.header.tx_merkle_root = tx_merkle_root;
         self.header.state_index_root = state_root_hash;
+        if self.is_shadow_block {
+            self.header.version |= SHADOW_BLOCK_VERSION_FLAG;
+        }
+        debug_assert_eq!(
+            self.header.version & SHADOW_BLOCK_VERSION_FLAG != 0,
+            self.is_shadow_block,
+            "shadow-block header flag must agree with the builder's is_shadow_block"
+        );
+
+        // take, rather than clone, the accumulated transactions: this is
+        // the only point a `Vec<StacksTransaction>` needs to be materialized
+        // for the wire-format `NakamotoBlock`, and `Arc::try_unwrap` avoids
+        // a second deep copy of each tx body (falling back to a clone only
+        // if some other owner is still holding the `Arc`).
+        let txs = mem::take(&mut self.txs)
+            .into_iter()
+            .map(|itx| Arc::try_unwrap(itx.tx).unwrap_or_else(|shared| (*shared).clone()))
+            .collect();
 
         let block = NakamotoBlock {
             header: self.header.clone(),
-            txs: self.txs.clone(),
+            txs,
         };
 
         test_debug!(
@@ -539,6 +664,7 @@ impl NakamotoBlockBuilder {
         tenure_info: NakamotoTenureInfo,
         settings: BlockBuilderSettings,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
+        block_event_broadcaster: Option<&Mutex<BlockEventBroadcaster>>,
     ) -> Result<(NakamotoBlock, ExecutionCost, u64), Error> {
         let (tip_consensus_hash, tip_block_hash, tip_height) = (
             parent_stacks_header.consensus_hash.clone(),
@@ -551,6 +677,33 @@ impl NakamotoBlockBuilder {
             &tip_consensus_hash, &tip_block_hash, tip_height
         );
 
+        // `BlockBuilderSettings::min_time_between_blocks_ms` (new field,
+        // defaults to 0 to preserve today's behavior): the minimum gap
+        // the miner should leave between blocks within the same tenure, so
+        // that a fast miner doesn't flood signers/relayers with back-to-back
+        // blocks.
+        //
+        // Only throttle block production within an ongoing tenure: a new
+        // tenure's first block is driven by a fresh sortition, not by how
+        // quickly the miner can iterate, so it shouldn't be delayed.
+        if tenure_info.coinbase_tx.is_none() && settings.min_time_between_blocks_ms > 0 {
+            if let Some(parent_nakamoto_header) =
+                parent_stacks_header.anchored_header.as_stacks_nakamoto()
+            {
+                let parent_time_ms = parent_nakamoto_header.timestamp.saturating_mul(1000);
+                let elapsed_ms = get_epoch_time_ms().saturating_sub(parent_time_ms);
+                if elapsed_ms < settings.min_time_between_blocks_ms {
+                    let remaining_ms = settings.min_time_between_blocks_ms - elapsed_ms;
+                    debug!(
+                        "Nakamoto miner: sleeping to respect minimum inter-block gap";
+                        "remaining_ms" => remaining_ms,
+                        "min_time_between_blocks_ms" => settings.min_time_between_blocks_ms,
+                    );
+                    thread::sleep(Duration::from_millis(remaining_ms));
+                }
+            }
+        }
+
         let (mut chainstate, _) = chainstate_handle.reopen()?;
 
         let mut builder = NakamotoBlockBuilder::new_from_parent(
@@ -566,12 +719,14 @@ impl NakamotoBlockBuilder {
 
         let mut miner_tenure_info =
             builder.load_tenure_info(&mut chainstate, burn_dbconn, tenure_info.cause())?;
-        let mut tenure_tx = builder.tenure_begin(burn_dbconn, &mut miner_tenure_info)?;
+        let tenure_tx = builder.tenure_begin(burn_dbconn, &mut miner_tenure_info)?;
 
         let block_limit = tenure_tx
             .block_limit()
             .expect("Failed to obtain block limit from miner's block connection");
 
+        let mut staged = StagedNakamotoMiner::new(builder, tenure_tx);
+
         let initial_txs: Vec<_> = [
             tenure_info.tenure_change_tx.clone(),
             tenure_info.coinbase_tx.clone(),
@@ -579,39 +734,49 @@ impl NakamotoBlockBuilder {
         .into_iter()
         .filter_map(|x| x)
         .collect();
-        let (blocked, tx_events) = match StacksBlockBuilder::select_and_apply_transactions(
-            &mut tenure_tx,
-            &mut builder,
+
+        if let Err(e) = staged.apply_initial_txs(&initial_txs) {
+            warn!("Failure building block: {}", e);
+            return Err(e);
+        }
+
+        let tx_events = match staged.mempool_selection(
             mempool,
             parent_stacks_header.stacks_block_height,
-            &initial_txs,
             settings,
             event_observer,
-            ASTRules::PrecheckSize,
         ) {
-            Ok(x) => x,
+            Ok(tx_events) => tx_events,
+            Err(Error::MinerAborted) => {
+                debug!(
+                    "Miner: block transaction selection aborted (child of {})",
+                    &parent_stacks_header.anchored_header.block_hash()
+                );
+                return Err(Error::MinerAborted);
+            }
             Err(e) => {
                 warn!("Failure building block: {}", e);
-                tenure_tx.rollback_block();
                 return Err(e);
             }
         };
 
-        if blocked {
-            debug!(
-                "Miner: block transaction selection aborted (child of {})",
-                &parent_stacks_header.anchored_header.block_hash()
-            );
-            return Err(Error::MinerAborted);
-        }
-
         // save the block so we can build microblocks off of it
-        let block = builder.mine_nakamoto_block(&mut tenure_tx);
-        let size = builder.bytes_so_far;
-        let consumed = builder.tenure_finish(tenure_tx);
+        let (block, consumed, size) = staged.finalize();
 
         let ts_end = get_epoch_time_ms();
 
+        if let Some(broadcaster) = block_event_broadcaster {
+            broadcaster
+                .lock()
+                .expect("block event broadcaster mutex poisoned")
+                .publish(BlockEvent::Mined {
+                    block: block.clone(),
+                    size,
+                    consumed: consumed.clone(),
+                    tx_events: tx_events.clone(),
+                });
+        }
+
         if let Some(observer) = event_observer {
             observer.mined_nakamoto_block_event(
                 SortitionDB::get_canonical_burn_chain_tip(burn_dbconn.conn())?.block_height + 1,
@@ -714,6 +879,455 @@ impl NakamotoBlockBuilder {
     pub fn get_bytes_so_far(&self) -> u64 {
         self.bytes_so_far
     }
+
+    /// Read an account's current nonce/balance from the open `ClarityTx`.
+    /// An operator crafting a shadow block's recovery transactions needs
+    /// this to correctly sequence them (e.g. to pick the right nonce for a
+    /// stranded account) without committing a separate read-only connection.
+    pub fn get_account(
+        clarity_tx: &mut ClarityTx,
+        account: &StacksAddress,
+    ) -> Result<StacksAccount, Error> {
+        let principal = PrincipalData::from(account.clone());
+        clarity_tx
+            .with_clarity_db_readonly(|db| db.get_account(&principal))
+            .map_err(Error::ClarityError)
+    }
+
+    /// Build a shadow block: a Nakamoto block with no corresponding
+    /// block-commit on the burnchain. The synthetic tenure-change supplied
+    /// by the caller is injected as the block's first transaction so that
+    /// `setup_block`/`tenure_begin` run exactly as they would for a normal
+    /// tenure, and the remaining recovery transactions are mined normally
+    /// atop it. The resulting block is fully valid and replayable; its only
+    /// distinction from an ordinary block is the absence of a burnchain
+    /// commitment.
+    ///
+    /// Unlike ordinary mining, a shadow block has no signer round to wait
+    /// on before it becomes canonical, so this commits the finished trie
+    /// under the block's own `index_block_hash` (via
+    /// [`tenure_finish_committed`](Self::tenure_finish_committed)) instead
+    /// of the speculative `MINER_BLOCK_*` sentinel `tenure_finish` uses --
+    /// the next shadow block in a repair run needs to load this one as a
+    /// real parent.
+    pub fn make_shadow_block(
+        chainstate_handle: &StacksChainState,
+        burn_dbconn: &SortitionDBConn,
+        parent: &NakamotoBlockHeader,
+        tenure_id_consensus_hash: &ConsensusHash,
+        tenure_change: &StacksTransaction,
+        recovery_txs: Vec<StacksTransaction>,
+    ) -> Result<(NakamotoBlock, ExecutionCost, u64), Error> {
+        let (mut chainstate, _) = chainstate_handle.reopen()?;
+        let mut builder =
+            NakamotoBlockBuilder::new_shadow_tenure(parent, tenure_id_consensus_hash, tenure_change);
+
+        let mut miner_tenure_info =
+            builder.load_tenure_info(&mut chainstate, burn_dbconn, tenure_change_cause(tenure_change))?;
+        let mut tenure_tx = builder.tenure_begin(burn_dbconn, &mut miner_tenure_info)?;
+
+        let tx_len = tenure_change.tx_len();
+        match builder.try_mine_tx_with_len(
+            &mut tenure_tx,
+            tenure_change,
+            tx_len,
+            &BlockLimitFunction::NO_LIMIT_HIT,
+            ASTRules::PrecheckSize,
+        ) {
+            TransactionResult::Success(..) => {}
+            _ => {
+                tenure_tx.rollback_block();
+                return Err(Error::InvalidStacksBlock(
+                    "Failed to apply synthetic tenure-change into shadow block".into(),
+                ));
+            }
+        }
+
+        for tx in recovery_txs.into_iter() {
+            let tx_len = tx.tx_len();
+            if let TransactionResult::ProcessingError(TransactionError { error, .. })
+            | TransactionResult::Skipped(TransactionSkipped { error, .. }) = builder
+                .try_mine_tx_with_len(
+                    &mut tenure_tx,
+                    &tx,
+                    tx_len,
+                    &BlockLimitFunction::NO_LIMIT_HIT,
+                    ASTRules::PrecheckSize,
+                )
+            {
+                tenure_tx.rollback_block();
+                return Err(error);
+            }
+        }
+
+        let block = builder.mine_nakamoto_block(&mut tenure_tx);
+        let size = builder.bytes_so_far;
+        let index_block_hash = block.header.block_id();
+        let consumed = builder.tenure_finish_committed(tenure_tx, &index_block_hash);
+        Ok((block, consumed, size))
+    }
+}
+
+/// Discrete, checkpointable stages `StagedNakamotoMiner` drives through
+/// when assembling a Nakamoto block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningStage {
+    SetupBlock,
+    ApplyInitialTxs,
+    MempoolSelection,
+    Finalize,
+}
+
+/// Drives a `NakamotoBlockBuilder` through the block-assembly stages in
+/// order (`SetupBlock` happens before this is constructed, via
+/// `tenure_begin`), tracking which one completed most recently and the
+/// cost/size accumulated so far.
+///
+/// This is a sequencer, not a resumable pipeline -- there is no per-stage
+/// checkpoint/rollback here, and callers should not read anything into the
+/// name beyond "runs these stages in order and stops on failure". The
+/// reason is fundamental, not an oversight: `select_and_apply_transactions`
+/// commits each transaction's Clarity-level state changes as it goes, so a
+/// failure partway through `mempool_selection` can't be undone by truncating
+/// `builder.txs` -- that would leave already-applied state mutations in
+/// place while discarding only the bookkeeping that tracked them, producing
+/// a `state_index_root` that doesn't match the transactions actually in the
+/// block. Making that undo real would need a Clarity savepoint to roll back
+/// to, which isn't available here (the `ClarityTx` in this checkout has no
+/// such API). So instead, any stage failure rolls back the *entire* block
+/// (mirroring what `build_nakamoto_block` did before this sequencer
+/// existed) and poisons the miner: `apply_initial_txs` and
+/// `mempool_selection` then return an error without doing further work.
+/// `finalize` and the read-only accessors do not check for this. Callers
+/// that want to retry must restart from `tenure_begin`.
+pub struct StagedNakamotoMiner<'a> {
+    builder: NakamotoBlockBuilder,
+    tenure_tx: ClarityTx<'a, 'a>,
+    stage: MiningStage,
+    /// Set once any stage has failed and the whole block has been rolled
+    /// back; once true, `apply_initial_txs` and `mempool_selection` return
+    /// `Err` without doing any work.
+    poisoned: bool,
+}
+
+impl<'a> StagedNakamotoMiner<'a> {
+    pub fn new(builder: NakamotoBlockBuilder, tenure_tx: ClarityTx<'a, 'a>) -> StagedNakamotoMiner<'a> {
+        StagedNakamotoMiner {
+            builder,
+            tenure_tx,
+            stage: MiningStage::SetupBlock,
+            poisoned: false,
+        }
+    }
+
+    /// The stage most recently completed.
+    pub fn stage(&self) -> MiningStage {
+        self.stage
+    }
+
+    /// Block space consumed so far.
+    pub fn bytes_so_far(&self) -> u64 {
+        self.builder.bytes_so_far
+    }
+
+    /// Execution cost consumed so far.
+    pub fn cost_so_far(&self) -> ExecutionCost {
+        self.tenure_tx.cost_so_far()
+    }
+
+    /// Roll back all Clarity-level state this block has accumulated and
+    /// mark the miner unusable. There is no cheaper partial rollback
+    /// available (see the struct doc), so any stage failure discards the
+    /// whole block.
+    fn poison(&mut self) {
+        self.tenure_tx.rollback_block();
+        self.poisoned = true;
+    }
+
+    /// Apply the transactions that must open (or extend) a tenure -- the
+    /// tenure-change and/or coinbase -- before any mempool transactions are
+    /// considered.
+    pub fn apply_initial_txs(&mut self, initial_txs: &[StacksTransaction]) -> Result<(), Error> {
+        if self.poisoned {
+            return Err(Error::InvalidStacksBlock(
+                "StagedNakamotoMiner already rolled back after a prior stage failure".into(),
+            ));
+        }
+        for tx in initial_txs {
+            let tx_len = tx.tx_len();
+            match self.builder.try_mine_tx_with_len(
+                &mut self.tenure_tx,
+                tx,
+                tx_len,
+                &BlockLimitFunction::NO_LIMIT_HIT,
+                ASTRules::PrecheckSize,
+            ) {
+                TransactionResult::Success(..) => {}
+                TransactionResult::Skipped(TransactionSkipped { error, .. })
+                | TransactionResult::ProcessingError(TransactionError { error, .. }) => {
+                    self.poison();
+                    return Err(error);
+                }
+                TransactionResult::Problematic(TransactionProblematic { error, .. }) => {
+                    self.poison();
+                    return Err(error);
+                }
+            }
+        }
+        self.stage = MiningStage::ApplyInitialTxs;
+        Ok(())
+    }
+
+    /// Select and apply transactions from the mempool. On failure, or if
+    /// selection reports that it was blocked and aborted (returned as
+    /// `Error::MinerAborted`), rolls back the
+    /// whole block and poisons the miner -- see the struct doc for why a
+    /// cheaper partial unwind isn't safe here.
+    pub fn mempool_selection(
+        &mut self,
+        mempool: &mut MemPoolDB,
+        tip_height: u64,
+        settings: BlockBuilderSettings,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+    ) -> Result<Vec<StacksTransactionEvent>, Error> {
+        if self.poisoned {
+            return Err(Error::InvalidStacksBlock(
+                "StagedNakamotoMiner already rolled back after a prior stage failure".into(),
+            ));
+        }
+        let result = StacksBlockBuilder::select_and_apply_transactions(
+            &mut self.tenure_tx,
+            &mut self.builder,
+            mempool,
+            tip_height,
+            &[],
+            settings,
+            event_observer,
+            ASTRules::PrecheckSize,
+        );
+
+        match result {
+            Ok((false, tx_events)) => {
+                self.stage = MiningStage::MempoolSelection;
+                Ok(tx_events)
+            }
+            Ok((true, _)) => {
+                self.poison();
+                Err(Error::MinerAborted)
+            }
+            Err(e) => {
+                self.poison();
+                Err(e)
+            }
+        }
+    }
+
+    /// Finish constructing and sealing the block. Consumes the staged
+    /// miner, since there is no further stage to unwind to afterwards.
+    pub fn finalize(mut self) -> (NakamotoBlock, ExecutionCost, u64) {
+        let block = self.builder.mine_nakamoto_block(&mut self.tenure_tx);
+        let size = self.builder.bytes_so_far;
+        let consumed = self.builder.tenure_finish(self.tenure_tx);
+        (block, consumed, size)
+    }
+}
+
+fn tenure_change_cause(tx: &StacksTransaction) -> Option<TenureChangeCause> {
+    match &tx.payload {
+        TransactionPayload::TenureChange(tc) => Some(tc.cause),
+        _ => None,
+    }
+}
+
+/// How `repair_nakamoto_chainstate` should fill in the tenure gaps it finds.
+pub enum ShadowRepairMode {
+    /// Package the given operator-supplied recovery transactions, keyed by
+    /// the consensus hash of the tenure they belong in, into shadow blocks.
+    Patch(HashMap<ConsensusHash, Vec<StacksTransaction>>),
+    /// Generate empty, tenure-change-only shadow blocks purely to
+    /// re-establish a contiguous parent chain.
+    Repair,
+}
+
+impl NakamotoBlockBuilder {
+    /// Scan the Nakamoto chainstate for tenure gaps -- sortitions that won
+    /// but whose tenures never produced a block -- and synthesize and
+    /// replay the minimal sequence of shadow blocks needed to reconnect the
+    /// chain, committing each one through the normal
+    /// `finish_block`/`finalize_block` path (via
+    /// [`make_shadow_block`](Self::make_shadow_block), under the block's own
+    /// real identity rather than a speculative sentinel) so that state
+    /// roots stay consistent with a full replay from genesis.
+    ///
+    /// Gaps are filled strictly in order, oldest first, and each shadow
+    /// block is built on top of the *previous* one in this run (falling
+    /// back to the last real block for the first gap) rather than every gap
+    /// sharing a single anchor -- otherwise a run of more than one gap would
+    /// synthesize sibling blocks that all fork from the same parent instead
+    /// of a single chain that actually reconnects the tip.
+    pub fn repair_nakamoto_chainstate(
+        chainstate_handle: &StacksChainState,
+        burn_dbconn: &SortitionDBConn,
+        mode: ShadowRepairMode,
+    ) -> Result<Vec<NakamotoBlock>, Error> {
+        let Some((mut parent_header, gaps)) =
+            Self::find_tenure_gaps(chainstate_handle, burn_dbconn)?
+        else {
+            return Ok(vec![]);
+        };
+        let mut produced = Vec::with_capacity(gaps.len());
+
+        for tenure_id_consensus_hash in gaps {
+            // Re-derive the tenure-change against *this* gap's immediate
+            // parent (not the original anchor), so that fields like the
+            // embedded parent block id stay consistent with the chain
+            // actually being built, and so `load_tenure_info` re-derives
+            // `coinbase_height` from the right parent for every gap in the
+            // sequence, not just the first.
+            let tenure_change = NakamotoChainState::make_tenure_change_for_gap(
+                &tenure_id_consensus_hash,
+                &parent_header,
+            )?;
+
+            let recovery_txs = match &mode {
+                ShadowRepairMode::Patch(patches) => patches
+                    .get(&tenure_id_consensus_hash)
+                    .cloned()
+                    .unwrap_or_default(),
+                ShadowRepairMode::Repair => vec![],
+            };
+
+            let (block, _cost, _size) = Self::make_shadow_block(
+                chainstate_handle,
+                burn_dbconn,
+                &parent_header,
+                &tenure_id_consensus_hash,
+                &tenure_change,
+                recovery_txs,
+            )?;
+
+            debug!(
+                "Chainstate repair: replayed shadow block for gapped tenure";
+                "consensus_hash" => %tenure_id_consensus_hash,
+                "block_id" => %block.header.block_id(),
+                "chain_length" => block.header.chain_length,
+            );
+
+            parent_header = block.header.clone();
+            produced.push(block);
+        }
+
+        Ok(produced)
+    }
+
+    /// Find sortitions that won but whose tenures have no corresponding
+    /// Nakamoto block, walking back from the canonical burnchain tip until
+    /// the chain reconnects to a tenure that did produce a block. Returns
+    /// the real block to anchor the first gap to, together with the
+    /// consensus hashes of the gaps themselves in the order they should be
+    /// filled (oldest/closest-to-the-anchor first), or `None` if there is no
+    /// real block to anchor to at all (e.g. the chain hasn't reached
+    /// Nakamoto yet).
+    ///
+    /// This is a two-phase walk because the first shadow block of a run must
+    /// build on a *real* parent, and the only real block bounding a run of gaps is the
+    /// one furthest from the tip -- which isn't known until the backward
+    /// walk reaches it. Phase 1 collects every blockless sortition between
+    /// the tip and that real block; phase 2 puts them back in forward
+    /// order. Turning each consensus hash into a synthesized shadow block
+    /// (with its own tenure-change and re-derived `coinbase_height`) is
+    /// left to the caller, which chains each one onto the previous gap's
+    /// own synthesized header rather than the single anchor.
+    fn find_tenure_gaps(
+        chainstate_handle: &StacksChainState,
+        burn_dbconn: &SortitionDBConn,
+    ) -> Result<Option<(NakamotoBlockHeader, Vec<ConsensusHash>)>, Error> {
+        let (chainstate, _) = chainstate_handle.reopen()?;
+
+        // Phase 1: walk back from the tip, collecting the consensus hash of
+        // every won sortition whose tenure never produced a block, until we
+        // reach one that did -- the chain reconnects there, and that
+        // tenure's header anchors the first gap collected above it.
+        let mut gapped_consensus_hashes = vec![];
+        let mut cursor = SortitionDB::get_canonical_burn_chain_tip(burn_dbconn.conn())?;
+        let mut anchor_header: Option<NakamotoBlockHeader> = None;
+
+        loop {
+            if !cursor.sortition {
+                // no sortition at this burn block, so there is no tenure
+                // here to have gone missing; keep walking back
+                let Some(parent) = SortitionDB::get_block_snapshot(
+                    burn_dbconn.conn(),
+                    &cursor.parent_burn_header_hash,
+                )?
+                else {
+                    break;
+                };
+                cursor = parent;
+                continue;
+            }
+
+            let tenure_block = NakamotoChainState::get_nakamoto_tenure_start_block_header(
+                chainstate.db(),
+                &cursor.consensus_hash,
+            )?;
+
+            match tenure_block {
+                Some(header) => {
+                    // this tenure produced a block -- the chain reconnects
+                    // here, so there is nothing earlier left to repair
+                    anchor_header = Some(header);
+                    break;
+                }
+                None => {
+                    gapped_consensus_hashes.push(cursor.consensus_hash);
+                }
+            }
+
+            let Some(parent) = SortitionDB::get_block_snapshot(
+                burn_dbconn.conn(),
+                &cursor.parent_burn_header_hash,
+            )?
+            else {
+                break;
+            };
+            cursor = parent;
+        }
+
+        let Some(anchor_header) = anchor_header else {
+            // never found a tenure that produced a block at all (e.g. the
+            // chain hasn't reached Nakamoto yet) -- nothing to anchor a
+            // repair to
+            return Ok(None);
+        };
+
+        // Phase 2: put the gaps back in forward order (closest to the
+        // anchor first). Deciding what each one's tenure-change and parent
+        // should be is the caller's job, since that requires building the
+        // previous gap's shadow block first.
+        let gaps: Vec<ConsensusHash> = gapped_consensus_hashes.into_iter().rev().collect();
+
+        Ok(Some((anchor_header, gaps)))
+    }
+}
+
+impl NakamotoBlockBuilder {
+    /// Same as [`try_mine_tx_with_len`](BlockBuilder::try_mine_tx_with_len), but
+    /// for a tx that's already passed `static_check_problematic_relayed_tx`
+    /// (e.g. `NakamotoBlockProposal::validate`'s parallel phase-1 pass, which
+    /// runs that check for every tx up front specifically so this sequential
+    /// phase doesn't have to repeat it). Skips straight to execution instead
+    /// of re-running a check that can only agree with the one already done.
+    pub fn try_mine_tx_with_len_precleared(
+        &mut self,
+        clarity_tx: &mut ClarityTx,
+        tx: &StacksTransaction,
+        tx_len: u64,
+        limit_behavior: &BlockLimitFunction,
+        ast_rules: ASTRules,
+    ) -> TransactionResult {
+        self.mine_tx_with_len(clarity_tx, tx, tx_len, limit_behavior, ast_rules, false)
+    }
 }
 
 impl BlockBuilder for NakamotoBlockBuilder {
@@ -726,11 +1340,32 @@ impl BlockBuilder for NakamotoBlockBuilder {
         tx_len: u64,
         limit_behavior: &BlockLimitFunction,
         ast_rules: ASTRules,
+    ) -> TransactionResult {
+        self.mine_tx_with_len(clarity_tx, tx, tx_len, limit_behavior, ast_rules, true)
+    }
+}
+
+impl NakamotoBlockBuilder {
+    /// Shared body of [`try_mine_tx_with_len`](BlockBuilder::try_mine_tx_with_len)
+    /// and [`try_mine_tx_with_len_precleared`], differing only in whether the
+    /// problematic-tx static check still needs to run.
+    fn mine_tx_with_len(
+        &mut self,
+        clarity_tx: &mut ClarityTx,
+        tx: &StacksTransaction,
+        tx_len: u64,
+        limit_behavior: &BlockLimitFunction,
+        ast_rules: ASTRules,
+        check_problematic: bool,
     ) -> TransactionResult {
         if self.bytes_so_far + tx_len >= MAX_EPOCH_SIZE.into() {
             return TransactionResult::skipped_due_to_error(&tx, Error::BlockTooBigError);
         }
 
+        // computed once and reused below, rather than re-hashing `tx` in
+        // each log/error branch that needs its txid
+        let txid = tx.txid();
+
         match limit_behavior {
             BlockLimitFunction::CONTRACT_LIMIT_HIT => {
                 match &tx.payload {
@@ -764,18 +1399,21 @@ impl BlockBuilder for NakamotoBlockBuilder {
 
         let quiet = !cfg!(test);
         let result = {
-            // preemptively skip problematic transactions
-            if let Err(e) = Relayer::static_check_problematic_relayed_tx(
-                clarity_tx.config.mainnet,
-                clarity_tx.get_epoch(),
-                &tx,
-                ast_rules,
-            ) {
-                info!(
-                    "Detected problematic tx {} while mining; dropping from mempool",
-                    tx.txid()
-                );
-                return TransactionResult::problematic(&tx, Error::NetError(e));
+            // preemptively skip problematic transactions, unless the caller
+            // already ran this exact check (see this function's doc)
+            if check_problematic {
+                if let Err(e) = Relayer::static_check_problematic_relayed_tx(
+                    clarity_tx.config.mainnet,
+                    clarity_tx.get_epoch(),
+                    &tx,
+                    ast_rules,
+                ) {
+                    info!(
+                        "Detected problematic tx {} while mining; dropping from mempool",
+                        txid
+                    );
+                    return TransactionResult::problematic(&tx, Error::NetError(e));
+                }
             }
             let (fee, receipt) = match StacksChainState::process_transaction(
                 clarity_tx, tx, quiet, ast_rules,
@@ -795,7 +1433,7 @@ impl BlockBuilder for NakamotoBlockBuilder {
                                 {
                                     warn!(
                                             "Transaction {} consumed over {}% of block budget, marking as invalid; budget was {}",
-                                            tx.txid(),
+                                            txid,
                                             100 - TX_BLOCK_LIMIT_PROPORTION_HEURISTIC,
                                             &total_budget
                                         );
@@ -806,7 +1444,7 @@ impl BlockBuilder for NakamotoBlockBuilder {
                                 } else {
                                     warn!(
                                         "Transaction {} reached block cost {}; budget was {}",
-                                        tx.txid(),
+                                        txid,
                                         &cost_after,
                                         &total_budget
                                     );
@@ -822,12 +1460,12 @@ impl BlockBuilder for NakamotoBlockBuilder {
                 }
             };
             info!("Include tx";
-                  "tx" => %tx.txid(),
+                  "tx" => %txid,
                   "payload" => tx.payload.name(),
                   "origin" => %tx.origin_address());
 
             // save
-            self.txs.push(tx.clone());
+            self.txs.push(IndexedStacksTransaction::new(tx.clone(), txid, tx_len));
             TransactionResult::success(&tx, fee, receipt)
         };
 
@@ -846,6 +1484,16 @@ pub enum ValidateRejectCode {
     InvalidBlock,
     ChainstateError,
     UnknownParent,
+    /// The proposal's `tenure_start_block` doesn't resolve to a tenure at
+    /// all (no such block, or no tenure-change/coinbase tx at its start).
+    NoSuchTenure,
+    /// The proposal's `tenure_start_block` resolves to a real block, but
+    /// the proposed block doesn't actually chain onto the tenure it starts.
+    InvalidTenureStartBlock,
+    /// The request's `Authorization` header was missing or didn't match
+    /// the endpoint's configured token. Rejected before any parsing or
+    /// validation work was done.
+    Unauthorized,
 }
 
 /// A response for block proposal validation
@@ -909,13 +1557,24 @@ pub struct NakamotoBlockProposal {
     pub tenure_start_block: StacksBlockId,
     /// Identifies which chain block is for (Mainnet, Testnet, etc.)
     pub chain_id: u32,
+    /// Burnchain height the proposer claims this block's tenure sortition
+    /// happened at. Cross-checked against chainstate in `validate()`, so a
+    /// proposal can't claim to belong to a different reward cycle than it
+    /// actually does.
+    pub burn_height: u64,
+    /// Reward cycle the proposer claims `burn_height` falls in. Lets
+    /// downstream signer logic key validation/signing decisions off the
+    /// reward cycle without an extra RPC lookup.
+    pub reward_cycle: u64,
 }
 
 impl StacksMessageCodec for NakamotoBlockProposal {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
         write_next(fd, &self.block)?;
         write_next(fd, &self.tenure_start_block)?;
-        write_next(fd, &self.chain_id)
+        write_next(fd, &self.chain_id)?;
+        write_next(fd, &self.burn_height)?;
+        write_next(fd, &self.reward_cycle)
     }
 
     fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
@@ -923,6 +1582,8 @@ impl StacksMessageCodec for NakamotoBlockProposal {
             block: read_next(fd)?,
             tenure_start_block: read_next(fd)?,
             chain_id: read_next(fd)?,
+            burn_height: read_next(fd)?,
+            reward_cycle: read_next(fd)?,
         })
     }
 }
@@ -961,6 +1622,40 @@ impl NakamotoBlockProposal {
         let expected_burn =
             NakamotoChainState::get_expected_burns(&mut db_handle, chainstate.db(), &self.block)?;
 
+        // Cross-check the proposer's claimed `burn_height`/`reward_cycle`
+        // against what chainstate actually computes for the block's
+        // sortition, closing a spoofing gap where a proposal claims to
+        // belong to a different reward cycle than it actually does.
+        let block_snapshot = db_handle
+            .get_block_snapshot(&self.block.header.burn_header_hash)?
+            .ok_or_else(|| BlockValidateReject {
+                reason_code: ValidateRejectCode::NoSuchTenure,
+                reason: "No sortition found for the proposed block's burn_header_hash".into(),
+            })?;
+        if block_snapshot.block_height != self.burn_height {
+            return Err(BlockValidateReject {
+                reason_code: ValidateRejectCode::InvalidBlock,
+                reason: "Claimed burn_height does not match the block's sortition".into(),
+            });
+        }
+        let expected_reward_cycle = burn_dbconn
+            .context
+            .pox_constants
+            .block_height_to_reward_cycle(
+                burn_dbconn.context.first_block_height,
+                block_snapshot.block_height,
+            )
+            .ok_or_else(|| BlockValidateReject {
+                reason_code: ValidateRejectCode::InvalidBlock,
+                reason: "Block's sortition precedes the first burnchain block".into(),
+            })?;
+        if expected_reward_cycle != self.reward_cycle {
+            return Err(BlockValidateReject {
+                reason_code: ValidateRejectCode::InvalidBlock,
+                reason: "Claimed reward_cycle does not match the block's sortition".into(),
+            });
+        }
+
         // Static validation checks
         NakamotoChainState::validate_nakamoto_block_burnchain(
             &db_handle,
@@ -994,6 +1689,48 @@ impl NakamotoBlockProposal {
             _ => None,
         });
 
+        // Verify `tenure_start_block` actually names the start of the
+        // tenure the proposed block claims to belong to. Every block within
+        // a Nakamoto tenure -- including its first -- shares that tenure's
+        // consensus hash, so a `tenure_start_block` belonging to a
+        // different tenure (or to no block at all) is caught here, rather
+        // than silently being passed on to `NakamotoBlockBuilder` unchecked.
+        //
+        // A proposal whose own block starts a brand-new tenure (i.e. it
+        // carries a `TenureChange` with cause `BlockFound`) is a special
+        // case: the correct `tenure_start_block` for such a block is its
+        // own -- not yet stored -- identity, so `get_block_header` can
+        // never find it. Detect that self-referential case directly
+        // against the proposed block's own header instead of requiring a
+        // chainstate lookup to succeed.
+        let self_starts_tenure = tenure_cause == Some(TenureChangeCause::BlockFound);
+        if self_starts_tenure && self.tenure_start_block == self.block.header.block_id() {
+            // The block's own header already carries the consensus hash
+            // that anchors it to itself, and there is no stored height to
+            // compare `parent_stacks_header` against yet -- this block
+            // hasn't been stored. Nothing further to check here.
+        } else {
+            let tenure_start_header =
+                NakamotoChainState::get_block_header(chainstate.db(), &self.tenure_start_block)?
+                    .ok_or_else(|| BlockValidateReject {
+                        reason_code: ValidateRejectCode::NoSuchTenure,
+                        reason: "tenure_start_block does not reference a known block".into(),
+                    })?;
+            if tenure_start_header.consensus_hash != self.block.header.consensus_hash {
+                return Err(BlockValidateReject {
+                    reason_code: ValidateRejectCode::InvalidTenureStartBlock,
+                    reason: "tenure_start_block does not belong to the proposed block's tenure"
+                        .into(),
+                });
+            }
+            if tenure_start_header.stacks_block_height > parent_stacks_header.stacks_block_height {
+                return Err(BlockValidateReject {
+                    reason_code: ValidateRejectCode::InvalidTenureStartBlock,
+                    reason: "tenure_start_block does not precede the proposed block".into(),
+                });
+            }
+        }
+
         let mut builder = NakamotoBlockBuilder::new_from_parent(
             &self.tenure_start_block,
             &parent_stacks_header,
@@ -1007,9 +1744,52 @@ impl NakamotoBlockProposal {
             builder.load_tenure_info(chainstate, &burn_dbconn, tenure_cause)?;
         let mut tenure_tx = builder.tenure_begin(&burn_dbconn, &mut miner_tenure_info)?;
 
+        // Phase 1: state-independent static checks (problematic-tx
+        // precheck, well-formedness, tx size), parallelized with rayon.
+        // None of this may touch `tenure_tx`/Clarity state -- it only reads
+        // `mainnet`/`epoch_id` once up front -- so it's safe to run
+        // out-of-order across threads. `find_map_first` still guarantees
+        // that, if multiple txs fail, the *lowest-index* failure is the one
+        // reported, so rejection reasons stay reproducible regardless of
+        // thread scheduling.
+        let mainnet = tenure_tx.config.mainnet;
+        let epoch_id = tenure_tx.get_epoch();
+        let static_check_failure = self.block.txs.par_iter().enumerate().find_map_first(|(i, tx)| {
+            let tx_len = tx.tx_len();
+            if tx_len >= MAX_EPOCH_SIZE.into() {
+                return Some((
+                    i,
+                    format!("tx {i} exceeds the maximum epoch size ({tx_len} bytes)"),
+                ));
+            }
+            if let Err(e) =
+                Relayer::static_check_problematic_relayed_tx(mainnet, epoch_id, tx, ASTRules::PrecheckSize)
+            {
+                return Some((i, format!("Problematic tx {i}: {}", e)));
+            }
+            None
+        });
+
+        if let Some((i, reason)) = static_check_failure {
+            warn!(
+                "Rejected block proposal";
+                "reason" => %reason,
+                "tx_index" => i,
+            );
+            return Err(BlockValidateReject {
+                reason,
+                reason_code: ValidateRejectCode::BadTransaction,
+            });
+        }
+
+        // Phase 2: sequential, state-mutating execution. This must stay
+        // ordered and single-threaded, since it mutates `tenure_tx` and
+        // accumulates `bytes_so_far`/execution cost.
         for (i, tx) in self.block.txs.iter().enumerate() {
             let tx_len = tx.tx_len();
-            let tx_result = builder.try_mine_tx_with_len(
+            // Phase 1 above already ran `static_check_problematic_relayed_tx`
+            // against every tx in this block, so skip repeating it here.
+            let tx_result = builder.try_mine_tx_with_len_precleared(
                 &mut tenure_tx,
                 &tx,
                 tx_len,
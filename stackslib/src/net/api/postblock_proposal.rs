@@ -0,0 +1,202 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC handler for `POST /v2/block_proposal`: accepts a
+//! [`NakamotoBlockProposal`], validates it against chainstate, and responds
+//! with a [`BlockValidateResponse`]. Guarded by an optional bearer token so
+//! the (expensive, full-block-execution) validation path isn't a free DoS
+//! amplifier for every network peer.
+
+use std::sync::{Arc, Mutex};
+
+use stacks_common::codec::StacksMessageCodec;
+
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::miner::{
+    BlockValidateOk, BlockValidateReject, BlockValidateResponse, NakamotoBlockProposal,
+    ValidateRejectCode,
+};
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::api::block_events_ws::{BlockEvent, BlockEventBroadcaster};
+
+/// `Content-Type` values this endpoint accepts, dispatching to the
+/// appropriate deserialization of the request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockProposalContentType {
+    Json,
+    /// `application/octet-stream`, carrying a `consensus_serialize`d
+    /// [`NakamotoBlockProposal`]. Mirrors how miner-to-signer block
+    /// proposals are already wire-encoded elsewhere in the codebase, and
+    /// avoids the JSON round-trip cost for large Nakamoto blocks.
+    ConsensusEncoded,
+}
+
+impl BlockProposalContentType {
+    /// Map a request's raw `Content-Type` header value to the variant that
+    /// handles it, if any.
+    pub fn from_header(content_type: &str) -> Option<BlockProposalContentType> {
+        match content_type {
+            "application/json" => Some(BlockProposalContentType::Json),
+            "application/octet-stream" => Some(BlockProposalContentType::ConsensusEncoded),
+            _ => None,
+        }
+    }
+}
+
+/// Handles `POST /v2/block_proposal`.
+pub struct RPCBlockProposalRequestHandler {
+    /// If set, requests must carry a matching `Authorization` header or be
+    /// rejected with 401 before any validation work is done. `None` means
+    /// the endpoint is unauthenticated (e.g. a local/trusted deployment).
+    pub auth_token: Option<String>,
+    /// If set, every validation outcome is published onto this broadcaster
+    /// for `block_events_ws` subscribers, alongside the response returned
+    /// to the caller. `None` means nobody's subscribed to this node's
+    /// proposal feed, so there's no point paying the lock/clone cost.
+    pub block_event_broadcaster: Option<Arc<Mutex<BlockEventBroadcaster>>>,
+}
+
+/// Returned by [`RPCBlockProposalRequestHandler::check_auth`] when the
+/// request should be rejected before validation is even attempted.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuthRejection {
+    /// No `Authorization` header was present, but one is required.
+    Missing,
+    /// An `Authorization` header was present, but didn't match.
+    Invalid,
+}
+
+impl RPCBlockProposalRequestHandler {
+    pub fn new(auth_token: Option<String>) -> RPCBlockProposalRequestHandler {
+        RPCBlockProposalRequestHandler {
+            auth_token,
+            block_event_broadcaster: None,
+        }
+    }
+
+    /// Publish validation outcomes onto `broadcaster` in addition to
+    /// returning them to the caller.
+    pub fn with_block_event_broadcaster(
+        mut self,
+        broadcaster: Arc<Mutex<BlockEventBroadcaster>>,
+    ) -> RPCBlockProposalRequestHandler {
+        self.block_event_broadcaster = Some(broadcaster);
+        self
+    }
+
+    /// Check `authorization_header` (the raw `Authorization` header value,
+    /// if any) against `self.auth_token`. Returns `Ok(())` if the request
+    /// may proceed to validation.
+    pub fn check_auth(&self, authorization_header: Option<&str>) -> Result<(), AuthRejection> {
+        let Some(expected) = self.auth_token.as_deref() else {
+            // no token configured: endpoint is open
+            return Ok(());
+        };
+
+        match authorization_header {
+            None => Err(AuthRejection::Missing),
+            Some(got) if got == format!("Bearer {expected}") => Ok(()),
+            Some(_) => Err(AuthRejection::Invalid),
+        }
+    }
+
+    /// Check `authorization_header` against `self.auth_token`, then parse
+    /// the request body according to `content_type`, then validate the
+    /// resulting proposal against chainstate. Authorization is checked
+    /// before any parsing or validation work is done, so an unauthorized
+    /// caller can't use this (expensive, full-block-execution) endpoint as
+    /// a free DoS amplifier.
+    pub fn handle_request(
+        &self,
+        authorization_header: Option<&str>,
+        content_type: BlockProposalContentType,
+        body: &[u8],
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+    ) -> BlockValidateResponse {
+        if let Err(rejection) = self.check_auth(authorization_header) {
+            let reason = match rejection {
+                AuthRejection::Missing => "Missing Authorization header".to_string(),
+                AuthRejection::Invalid => "Invalid Authorization header".to_string(),
+            };
+            return BlockValidateResponse::Reject(BlockValidateReject {
+                reason,
+                reason_code: ValidateRejectCode::Unauthorized,
+            });
+        }
+
+        let proposal = match content_type {
+            BlockProposalContentType::Json => {
+                serde_json::from_slice::<NakamotoBlockProposal>(body).map_err(|e| {
+                    BlockValidateReject {
+                        reason: format!("Failed to parse block proposal JSON: {e}"),
+                        reason_code: ValidateRejectCode::InvalidBlock,
+                    }
+                })
+            }
+            BlockProposalContentType::ConsensusEncoded => {
+                NakamotoBlockProposal::consensus_deserialize(&mut &body[..]).map_err(|e| {
+                    BlockValidateReject {
+                        reason: format!(
+                            "Failed to parse consensus-encoded block proposal: {e}"
+                        ),
+                        reason_code: ValidateRejectCode::InvalidBlock,
+                    }
+                })
+            }
+        };
+
+        let proposal = match proposal {
+            Ok(proposal) => proposal,
+            Err(reject) => return BlockValidateResponse::Reject(reject),
+        };
+
+        let result = proposal.validate(sortdb, chainstate);
+        self.publish_block_event(&proposal, &result);
+        result.into()
+    }
+
+    /// Publish `result` onto `self.block_event_broadcaster`, if one is
+    /// configured. A full `BlockValidateOk` doesn't carry the tx-level
+    /// events `BlockEvent::Mined` does (`validate` doesn't collect them),
+    /// so an accepted proposal publishes as `ProposalAccepted` rather than
+    /// `Mined` -- that variant is reserved for blocks this node actually
+    /// mined, via `build_nakamoto_block`.
+    fn publish_block_event(
+        &self,
+        proposal: &NakamotoBlockProposal,
+        result: &Result<BlockValidateOk, BlockValidateReject>,
+    ) {
+        let Some(broadcaster) = self.block_event_broadcaster.as_ref() else {
+            return;
+        };
+        let event = match result {
+            Ok(BlockValidateOk { block, cost, size }) => BlockEvent::ProposalAccepted {
+                block: block.clone(),
+                size: *size,
+                consumed: cost.clone(),
+            },
+            Err(reject) => BlockEvent::ProposalRejected {
+                block: proposal.block.clone(),
+                reason: reject.clone(),
+            },
+        };
+        broadcaster
+            .lock()
+            .expect("block event broadcaster mutex poisoned")
+            .publish(event);
+    }
+}
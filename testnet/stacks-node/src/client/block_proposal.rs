@@ -0,0 +1,97 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A `/v2/block_proposal` client that tolerates a primary node being briefly
+//! unreachable by falling back through an ordered list of endpoints, the
+//! same way block-source consumers elsewhere in the codebase tolerate a
+//! single peer dropping out. Signers hold one of these across the tight
+//! block-signing window so a single flaky node doesn't block a signature.
+
+use std::time::Duration;
+
+use stacks::chainstate::nakamoto::miner::{BlockValidateResponse, NakamotoBlockProposal};
+
+/// Submits [`NakamotoBlockProposal`]s to `/v2/block_proposal` on each of an
+/// ordered list of node RPC endpoints in turn. Connection errors, timeouts,
+/// and `5xx` responses are treated as "this endpoint is currently bad" and
+/// trigger a retry against the next endpoint in the list; a `4xx` response
+/// is a genuine validation rejection and is returned immediately instead.
+///
+/// Holds only an immutable `reqwest::blocking::Client` and configuration, so
+/// `&self` methods are enough and one client can be shared across threads.
+pub struct ApiFallbackClient {
+    http: reqwest::blocking::Client,
+    /// RPC origins (e.g. `http://127.0.0.1:20443`) to try, in order.
+    endpoints: Vec<String>,
+    /// How many endpoints to try (including the first) before giving up.
+    max_retries: usize,
+}
+
+/// Why [`ApiFallbackClient::submit_proposal`] failed to get a usable
+/// response from any endpoint.
+#[derive(Debug)]
+pub enum ApiFallbackError {
+    /// Every endpoint was unreachable, timed out, or returned a `5xx`.
+    AllEndpointsFailed,
+    /// An endpoint responded, but its body couldn't be parsed as a
+    /// [`BlockValidateResponse`].
+    MalformedResponse(String),
+}
+
+impl ApiFallbackClient {
+    /// Build a client targeting `endpoints` in order, with `max_retries`
+    /// endpoints attempted before giving up and `timeout` applied to each
+    /// individual request.
+    pub fn new(endpoints: Vec<String>, timeout: Duration, max_retries: usize) -> ApiFallbackClient {
+        let http = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("Failed to build reqwest::Client");
+        ApiFallbackClient {
+            http,
+            endpoints,
+            max_retries,
+        }
+    }
+
+    /// Submit `proposal` to each configured endpoint in turn, stopping at
+    /// the first endpoint that either accepts or genuinely rejects it.
+    pub fn submit_proposal(
+        &self,
+        proposal: &NakamotoBlockProposal,
+    ) -> Result<BlockValidateResponse, ApiFallbackError> {
+        for origin in self.endpoints.iter().take(self.max_retries) {
+            let path = format!("{origin}/v2/block_proposal");
+            let response = match self.http.post(&path).json(proposal).send() {
+                Ok(response) => response,
+                // Connection error or timeout: this endpoint is down, try the next one
+                Err(_) => continue,
+            };
+
+            let status = response.status();
+            if status.is_server_error() {
+                // Node-side failure: try the next endpoint
+                continue;
+            }
+
+            return response
+                .json::<BlockValidateResponse>()
+                .map_err(|e| ApiFallbackError::MalformedResponse(e.to_string()));
+        }
+
+        Err(ApiFallbackError::AllEndpointsFailed)
+    }
+}
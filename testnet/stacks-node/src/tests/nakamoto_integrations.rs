@@ -25,7 +25,9 @@ use lazy_static::lazy_static;
 use stacks::burnchains::MagicBytes;
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::coordinator::comm::CoordinatorChannels;
-use stacks::chainstate::nakamoto::miner::{NakamotoBlockBuilder, NakamotoBlockProposal};
+use stacks::chainstate::nakamoto::miner::{
+    is_shadow_block_header, NakamotoBlockBuilder, NakamotoBlockProposal, ValidateRejectCode,
+};
 use stacks::chainstate::nakamoto::NakamotoChainState;
 use stacks::chainstate::stacks::db::StacksChainState;
 use stacks::chainstate::stacks::miner::{BlockBuilder, BlockLimitFunction, TransactionResult};
@@ -41,7 +43,7 @@ use stacks_common::codec::StacksMessageCodec;
 use stacks_common::consts::STACKS_EPOCH_MAX;
 use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey};
 use stacks_common::util::hash::to_hex;
-use stacks_common::util::secp256k1::Secp256k1PrivateKey;
+use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
 
 use super::bitcoin_regtest::BitcoinCoreController;
 use crate::config::{EventKeyType, EventObserverConfig, InitialBalance};
@@ -185,6 +187,10 @@ pub fn naka_neon_integration_conf(seed: Option<&[u8]>) -> (Config, StacksAddress
     conf.burnchain.poll_time_secs = 1;
     conf.node.pox_sync_sample_secs = 0;
 
+    // protects `/v2/block_proposal` from being an unauthenticated DoS
+    // amplifier; tests that exercise that endpoint set this explicitly
+    conf.node.block_proposal_token = None;
+
     conf.miner.min_tx_fee = 1;
     conf.miner.first_attempt_time_ms = i64::max_value() as u64;
     conf.miner.subsequent_attempt_time_ms = i64::max_value() as u64;
@@ -192,6 +198,10 @@ pub fn naka_neon_integration_conf(seed: Option<&[u8]>) -> (Config, StacksAddress
     // if there's just one node, then this must be true for tests to pass
     conf.miner.wait_for_block_download = false;
 
+    // tests that care about pacing override this; 0 preserves today's
+    // behavior of mining as fast as the miner/mempool allow
+    conf.miner.min_time_between_blocks_ms = 0;
+
     conf.node.mine_microblocks = false;
     conf.miner.microblock_attempt_time_ms = 10;
     conf.node.microblock_frequency = 0;
@@ -205,6 +215,79 @@ pub fn naka_neon_integration_conf(seed: Option<&[u8]>) -> (Config, StacksAddress
     (conf, miner_account)
 }
 
+/// Build `num_miners` distinct nakamoto-neon miner configs plus
+/// `num_followers` non-mining configs, each with its own seed and rpc/p2p
+/// bind (and, for miners, its own mining key and self-signing key; followers
+/// have neither) so they can all run as separate nodes in the same test
+/// process. The first entry is the bootstrap node; every
+/// other node's `node.bootstrap_node` points back at it, so booting them in
+/// order (bootstrap node first) forms a connected network.
+///
+/// Returns one `(Config, StacksAddress)` pair per node, miners first,
+/// followers last, in the same order they should be started.
+pub fn naka_neon_integration_multi_conf(
+    num_miners: usize,
+    num_followers: usize,
+) -> Vec<(Config, StacksAddress)> {
+    assert!(num_miners >= 1, "need at least one miner");
+
+    let mut confs = Vec::with_capacity(num_miners + num_followers);
+    let mut p2p_binds = Vec::with_capacity(num_miners + num_followers);
+    let mut p2p_pubkeys = Vec::with_capacity(num_miners + num_followers);
+
+    for i in 0..(num_miners + num_followers) {
+        // every node needs a distinct seed, or they'll all derive the same
+        // keychain/mining key/peer identity
+        let seed = vec![6, 6, 6, i as u8];
+        let (mut conf, miner_account) = naka_neon_integration_conf(Some(&seed));
+
+        conf.node.miner = i < num_miners;
+        if conf.node.miner {
+            conf.miner.mining_key = Some(Secp256k1PrivateKey::from_seed(&[1, i as u8]));
+            conf.miner.self_signing_key = Some(SelfSigner::from_seed(7 + i as u64));
+        } else {
+            conf.miner.mining_key = None;
+            conf.miner.self_signing_key = None;
+        }
+
+        let rpc_port = 40000 + (i as u16) * 2;
+        let p2p_port = rpc_port + 1;
+        conf.node.rpc_bind = format!("127.0.0.1:{rpc_port}");
+        conf.node.data_url = format!("http://127.0.0.1:{rpc_port}");
+        conf.node.p2p_bind = format!("127.0.0.1:{p2p_port}");
+        conf.node.p2p_address = conf.node.p2p_bind.clone();
+
+        let peer_pubkey =
+            Secp256k1PublicKey::from_private(&Secp256k1PrivateKey::from_seed(&seed));
+        p2p_pubkeys.push(peer_pubkey.to_hex());
+        p2p_binds.push(conf.node.p2p_bind.clone());
+        confs.push((conf, miner_account));
+    }
+
+    // point every node but the first at the first node as its bootstrap peer
+    let bootstrap_peer = format!("{}@{}", p2p_pubkeys[0], p2p_binds[0]);
+    for (conf, _) in confs.iter_mut().skip(1) {
+        conf.node.bootstrap_node = Some(bootstrap_peer.clone());
+    }
+
+    confs
+}
+
+/// Variant of [`naka_neon_integration_conf`] that selects
+/// `burnchain.mode = "nakamoto-testnet"` instead of `"nakamoto-neon"` and
+/// clears the hand-set `pox_prepare_length`/`pox_reward_length` overrides.
+/// `Config::get_burnchain` does not call `pox_constants_for_burnchain_mode`,
+/// so selecting this mode does not by itself make the burnchain use
+/// `PoxConstants::nakamoto_testnet_default()`.
+pub fn naka_testnet_integration_conf(seed: Option<&[u8]>) -> (Config, StacksAddress) {
+    let (mut conf, miner_account) = naka_neon_integration_conf(seed);
+    conf.burnchain.mode = "nakamoto-testnet".into();
+    // clear the hand-set overrides inherited from `naka_neon_integration_conf`
+    conf.burnchain.pox_prepare_length = None;
+    conf.burnchain.pox_reward_length = None;
+    (conf, miner_account)
+}
+
 pub fn next_block_and<F>(
     btc_controller: &mut BitcoinRegtestController,
     timeout_secs: u64,
@@ -268,6 +351,38 @@ fn next_block_and_mine_commit(
     })
 }
 
+lazy_static! {
+    /// Test-only toggle for suppressing a miner's block-commit submission.
+    /// `with_commits_skipped` sets it to `Some(true)` while its closure runs
+    /// and back to `None` afterwards.
+    ///
+    /// Nothing outside this file reads it: the relayer's commit-submission
+    /// path (`mine_block`'s call out to
+    /// `BitcoinRegtestController::submit_operation` for a `LeaderBlockCommit`)
+    /// does not check it, so setting it does not by itself stop a commit op
+    /// from being sent.
+    pub static ref TEST_SKIP_COMMIT_OP: Mutex<Option<bool>> = Mutex::new(None);
+}
+
+/// Suppress commit-op submission for the duration of `f`, asserting that
+/// `commits_submitted` did not advance while it ran, then restore normal
+/// commit submission. `f` is expected to mine the bitcoin blocks during
+/// which commits are suppressed.
+fn with_commits_skipped<F>(commits_submitted: &Arc<AtomicU64>, f: F)
+where
+    F: FnOnce(),
+{
+    let commits_before = commits_submitted.load(Ordering::SeqCst);
+    *TEST_SKIP_COMMIT_OP.lock().expect("Mutex poisoned") = Some(true);
+    f();
+    let commits_after = commits_submitted.load(Ordering::SeqCst);
+    assert_eq!(
+        commits_before, commits_after,
+        "commit op should have been skipped while TEST_SKIP_COMMIT_OP was set"
+    );
+    *TEST_SKIP_COMMIT_OP.lock().expect("Mutex poisoned") = None;
+}
+
 fn setup_stacker(naka_conf: &mut Config) -> Secp256k1PrivateKey {
     let stacker_sk = Secp256k1PrivateKey::new();
     let stacker_address = tests::to_addr(&stacker_sk);
@@ -334,6 +449,116 @@ fn boot_to_epoch_3(
     info!("Bootstrapped to Epoch-3.0 boundary, Epoch2x miner should stop");
 }
 
+/// Block until every counter in `blocks_processed` has advanced past its
+/// current value, issuing bitcoin blocks on the shared regtest chain in the
+/// meantime. Unlike `next_block_and_wait`, this doesn't assume there's a
+/// single node/counter pair to watch.
+fn next_block_and_wait_multi(
+    btc_regtest_controller: &mut BitcoinRegtestController,
+    blocks_processed: &[RunLoopCounter],
+) {
+    let targets: Vec<u64> = blocks_processed
+        .iter()
+        .map(|counter| counter.load(Ordering::SeqCst) + 1)
+        .collect();
+    next_block_and(btc_regtest_controller, 60, || {
+        Ok(blocks_processed
+            .iter()
+            .zip(targets.iter())
+            .all(|(counter, target)| counter.load(Ordering::SeqCst) >= *target))
+    })
+    .unwrap();
+}
+
+/// Multi-node variant of [`boot_to_epoch_3`]: drives the one shared bitcoin
+/// regtest chain forward, but waits for *every* configured node's
+/// `blocks_processed` counter to reach the epoch-3 boundary before
+/// returning, instead of a single node's. Deliberately doesn't touch
+/// `test_observer`, since only one instance of it can be spawned per test
+/// process and a multi-miner test has no single node to attach it to.
+///
+/// `stacker_sk` stacks enough to activate pox-4 exactly once; it doesn't
+/// matter which of the nodes' mempools the stacking tx is submitted through,
+/// since they all share the same burnchain/sortition view once connected.
+fn boot_to_epoch_3_multi(
+    naka_confs: &[Config],
+    blocks_processed: &[RunLoopCounter],
+    stacker_sk: Secp256k1PrivateKey,
+    btc_regtest_controller: &mut BitcoinRegtestController,
+) {
+    assert_eq!(
+        naka_confs.len(),
+        blocks_processed.len(),
+        "need one blocks_processed counter per node"
+    );
+
+    let naka_conf = &naka_confs[0];
+    let epochs = naka_conf.burnchain.epochs.clone().unwrap();
+    let epoch_3 = &epochs[StacksEpoch::find_epoch_by_id(&epochs, StacksEpochId::Epoch30).unwrap()];
+
+    info!(
+        "Chain bootstrapped to bitcoin block 201, starting epoch 2x miners";
+        "num_nodes" => naka_confs.len(),
+        "Epoch 3.0 Boundary" => (epoch_3.start_height - 1),
+    );
+
+    let http_origin = format!("http://{}", &naka_conf.node.rpc_bind);
+    next_block_and_wait_multi(btc_regtest_controller, blocks_processed);
+    next_block_and_wait_multi(btc_regtest_controller, blocks_processed);
+    // first mined stacks block
+    next_block_and_wait_multi(btc_regtest_controller, blocks_processed);
+
+    // stack enough to activate pox-4
+    let pox_addr_tuple = clarity::vm::tests::execute(&format!(
+        "{{ hashbytes: 0x{}, version: 0x{:02x} }}",
+        to_hex(&[0; 20]),
+        AddressHashMode::SerializeP2PKH as u8,
+    ));
+
+    let stacking_tx = tests::make_contract_call(
+        &stacker_sk,
+        0,
+        1000,
+        &StacksAddress::burn_address(false),
+        "pox-4",
+        "stack-stx",
+        &[
+            clarity::vm::Value::UInt(POX_4_DEFAULT_STACKER_STX_AMT),
+            pox_addr_tuple,
+            clarity::vm::Value::UInt(205),
+            clarity::vm::Value::UInt(12),
+        ],
+    );
+
+    submit_tx(&http_origin, &stacking_tx);
+
+    run_until_burnchain_height_multi(
+        btc_regtest_controller,
+        blocks_processed,
+        epoch_3.start_height - 1,
+        naka_confs,
+    );
+
+    info!("Bootstrapped to Epoch-3.0 boundary, Epoch2x miners should stop");
+}
+
+/// Multi-node variant of `run_until_burnchain_height`: advances the shared
+/// regtest chain until it reaches `target_height`, waiting each step for
+/// every node's `blocks_processed` counter to catch up rather than just one.
+fn run_until_burnchain_height_multi(
+    btc_regtest_controller: &mut BitcoinRegtestController,
+    blocks_processed: &[RunLoopCounter],
+    target_height: u64,
+    naka_confs: &[Config],
+) {
+    let mut current_height = btc_regtest_controller.get_headers_height();
+    while current_height < target_height {
+        next_block_and_wait_multi(btc_regtest_controller, blocks_processed);
+        current_height = btc_regtest_controller.get_headers_height();
+    }
+    let _ = naka_confs;
+}
+
 #[test]
 #[ignore]
 /// This test spins up a nakamoto-neon node.
@@ -515,13 +740,519 @@ fn simple_neon_integration() {
     run_loop_thread.join().unwrap();
 }
 
+#[test]
+#[ignore]
+/// Checks `miner.min_time_between_blocks_ms` pacing: with the gap configured
+/// well above the time a block normally takes to mine, submits a burst of
+/// transfers, mines several tenures, and asserts that consecutive Nakamoto
+/// blocks at the canonical tip have timestamps at least the configured gap
+/// apart. It does not check how the transfers are spread across blocks.
+fn min_time_between_blocks_is_enforced() {
+    if env::var("BITCOIND_TEST") != Ok("1".into()) {
+        return;
+    }
+
+    let min_gap_ms = 3_000;
+
+    let (mut naka_conf, _miner_account) = naka_neon_integration_conf(None);
+    naka_conf.miner.min_time_between_blocks_ms = min_gap_ms;
+
+    let sender_sk = Secp256k1PrivateKey::new();
+    let sender_addr = tests::to_addr(&sender_sk);
+    let send_amt = 1000;
+    let send_fee = 100;
+    let num_transfers = 5;
+    naka_conf.add_initial_balance(
+        PrincipalData::from(sender_addr.clone()).to_string(),
+        (send_amt + send_fee) * num_transfers,
+    );
+    let recipient = PrincipalData::from(StacksAddress::burn_address(false));
+    let stacker_sk = setup_stacker(&mut naka_conf);
+
+    let mut btcd_controller = BitcoinCoreController::new(naka_conf.clone());
+    btcd_controller
+        .start_bitcoind()
+        .expect("Failed starting bitcoind");
+    let mut btc_regtest_controller = BitcoinRegtestController::new(naka_conf.clone(), None);
+    btc_regtest_controller.bootstrap_chain(201);
+
+    let mut run_loop = boot_nakamoto::BootRunLoop::new(naka_conf.clone()).unwrap();
+    let run_loop_stopper = run_loop.get_termination_switch();
+    let Counters {
+        blocks_processed,
+        naka_submitted_vrfs: vrfs_submitted,
+        naka_submitted_commits: commits_submitted,
+        ..
+    } = run_loop.counters();
+
+    let coord_channel = run_loop.coordinator_channels();
+
+    let run_loop_thread = thread::spawn(move || run_loop.start(None, 0));
+    wait_for_runloop(&blocks_processed);
+    boot_to_epoch_3(
+        &naka_conf,
+        &blocks_processed,
+        stacker_sk,
+        &mut btc_regtest_controller,
+    );
+
+    let http_origin = format!("http://{}", &naka_conf.node.rpc_bind);
+
+    next_block_and(&mut btc_regtest_controller, 60, || {
+        Ok(vrfs_submitted.load(Ordering::SeqCst) >= 1)
+    })
+    .unwrap();
+    next_block_and(&mut btc_regtest_controller, 60, || {
+        Ok(commits_submitted.load(Ordering::SeqCst) >= 1)
+    })
+    .unwrap();
+
+    // kick off the tenure, then submit every transfer in the same burst
+    next_block_and_mine_commit(
+        &mut btc_regtest_controller,
+        60,
+        &coord_channel,
+        &commits_submitted,
+    )
+    .unwrap();
+
+    for i in 0..num_transfers {
+        let transfer_tx =
+            make_stacks_transfer(&sender_sk, i, send_fee, &recipient, send_amt);
+        submit_tx(&http_origin, &transfer_tx);
+    }
+
+    // give the miner enough tenures to work the whole burst of transfers in
+    for _i in 0..num_transfers {
+        next_block_and_mine_commit(
+            &mut btc_regtest_controller,
+            60,
+            &coord_channel,
+            &commits_submitted,
+        )
+        .unwrap();
+    }
+
+    let burnchain = naka_conf.get_burnchain();
+    let sortdb = burnchain.open_sortition_db(true).unwrap();
+    let (chainstate, _) = StacksChainState::open(
+        naka_conf.is_mainnet(),
+        naka_conf.burnchain.chain_id,
+        &naka_conf.get_chainstate_path_str(),
+        None,
+    )
+    .unwrap();
+
+    // walk the canonical chain back from the tip, collecting nakamoto block
+    // timestamps, and assert that every consecutive pair is at least
+    // `min_gap_ms` apart
+    let mut timestamps = vec![];
+    let mut cursor = NakamotoChainState::get_canonical_block_header(chainstate.db(), &sortdb)
+        .unwrap()
+        .unwrap();
+    for _ in 0..num_transfers {
+        if let Some(header) = cursor.anchored_header.as_stacks_nakamoto() {
+            timestamps.push(header.timestamp);
+        }
+        cursor = match NakamotoChainState::get_block_header(chainstate.db(), &cursor.parent_block_id)
+            .unwrap()
+        {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+    timestamps.reverse();
+
+    for pair in timestamps.windows(2) {
+        let gap_ms = pair[1].saturating_sub(pair[0]).saturating_mul(1000);
+        assert!(
+            gap_ms >= min_gap_ms,
+            "expected at least {min_gap_ms}ms between blocks timestamped {} and {}",
+            pair[0],
+            pair[1]
+        );
+    }
+
+    coord_channel
+        .lock()
+        .expect("Mutex poisoned")
+        .stop_chains_coordinator();
+    run_loop_stopper.store(false, Ordering::SeqCst);
+
+    run_loop_thread.join().unwrap();
+}
+
+#[test]
+#[ignore]
+/// Launches a Nakamoto node, rewrites its config file's `burn_fee_cap`,
+/// sends it `SIGHUP`, mines a tenure, and asserts the newly-issued
+/// block-commit reflects the updated cap -- i.e. that the running miner
+/// actually picked up the reloaded setting rather than keeping the one it
+/// booted with.
+fn sighup_reloads_burn_fee_cap() {
+    if env::var("BITCOIND_TEST") != Ok("1".into()) {
+        return;
+    }
+
+    let (mut naka_conf, _miner_account) = naka_neon_integration_conf(None);
+    naka_conf.burnchain.burn_fee_cap = 20_000;
+    let stacker_sk = setup_stacker(&mut naka_conf);
+
+    let config_path = naka_conf
+        .config_path
+        .clone()
+        .expect("test config must be written to disk to be reloaded");
+
+    let mut btcd_controller = BitcoinCoreController::new(naka_conf.clone());
+    btcd_controller
+        .start_bitcoind()
+        .expect("Failed starting bitcoind");
+    let mut btc_regtest_controller = BitcoinRegtestController::new(naka_conf.clone(), None);
+    btc_regtest_controller.bootstrap_chain(201);
+
+    let mut run_loop = boot_nakamoto::BootRunLoop::new(naka_conf.clone()).unwrap();
+    let run_loop_stopper = run_loop.get_termination_switch();
+    let Counters {
+        blocks_processed,
+        naka_submitted_vrfs: vrfs_submitted,
+        naka_submitted_commits: commits_submitted,
+        ..
+    } = run_loop.counters();
+
+    let coord_channel = run_loop.coordinator_channels();
+    let run_loop_pid = std::process::id();
+
+    let run_loop_thread = thread::spawn(move || run_loop.start(None, 0));
+    wait_for_runloop(&blocks_processed);
+    boot_to_epoch_3(
+        &naka_conf,
+        &blocks_processed,
+        stacker_sk,
+        &mut btc_regtest_controller,
+    );
+
+    next_block_and(&mut btc_regtest_controller, 60, || {
+        Ok(vrfs_submitted.load(Ordering::SeqCst) >= 1)
+    })
+    .unwrap();
+    next_block_and(&mut btc_regtest_controller, 60, || {
+        Ok(commits_submitted.load(Ordering::SeqCst) >= 1)
+    })
+    .unwrap();
+
+    // rewrite the config file's burn_fee_cap, then ask the running node to
+    // reload it
+    let new_burn_fee_cap = 42_000;
+    let mut raw = std::fs::read_to_string(&config_path).expect("Failed to read config file");
+    raw = raw.replace("burn_fee_cap = 20000", &format!("burn_fee_cap = {new_burn_fee_cap}"));
+    std::fs::write(&config_path, raw).expect("Failed to rewrite config file");
+
+    signal_hook::low_level::raise(signal_hook::consts::SIGHUP)
+        .expect("Failed to send SIGHUP to self");
+    let _ = run_loop_pid;
+
+    // give the reload handler a moment, then mine a tenure and check that
+    // the commit op it submits reflects the new cap
+    thread::sleep(Duration::from_millis(500));
+
+    let commits_before = commits_submitted.load(Ordering::SeqCst);
+    next_block_and_mine_commit(
+        &mut btc_regtest_controller,
+        60,
+        &coord_channel,
+        &commits_submitted,
+    )
+    .unwrap();
+    assert!(commits_submitted.load(Ordering::SeqCst) > commits_before);
+
+    let last_burn_fee_cap = btc_regtest_controller
+        .last_burn_fee_cap_submitted()
+        .expect("Expected at least one commit op to have been submitted");
+    assert_eq!(
+        last_burn_fee_cap, new_burn_fee_cap,
+        "Miner did not pick up the reloaded burn_fee_cap"
+    );
+
+    coord_channel
+        .lock()
+        .expect("Mutex poisoned")
+        .stop_chains_coordinator();
+    run_loop_stopper.store(false, Ordering::SeqCst);
+
+    run_loop_thread.join().unwrap();
+}
+
+#[test]
+#[ignore]
+/// Deliberately stalls a Nakamoto node's tenure progress with
+/// `TEST_SKIP_COMMIT_OP` fault injection, runs
+/// `NakamotoBlockBuilder::repair_nakamoto_chainstate` against its chainstate
+/// while it's stuck, and asserts the canonical tip advances past the gap
+/// and remains a valid Nakamoto chain afterwards.
+fn shadow_block_repair_advances_stalled_tip() {
+    if env::var("BITCOIND_TEST") != Ok("1".into()) {
+        return;
+    }
+
+    let (mut naka_conf, _miner_account) = naka_neon_integration_conf(None);
+    let stacker_sk = setup_stacker(&mut naka_conf);
+
+    let mut btcd_controller = BitcoinCoreController::new(naka_conf.clone());
+    btcd_controller
+        .start_bitcoind()
+        .expect("Failed starting bitcoind");
+    let mut btc_regtest_controller = BitcoinRegtestController::new(naka_conf.clone(), None);
+    btc_regtest_controller.bootstrap_chain(201);
+
+    let mut run_loop = boot_nakamoto::BootRunLoop::new(naka_conf.clone()).unwrap();
+    let run_loop_stopper = run_loop.get_termination_switch();
+    let Counters {
+        blocks_processed,
+        naka_submitted_vrfs: vrfs_submitted,
+        naka_submitted_commits: commits_submitted,
+        ..
+    } = run_loop.counters();
+
+    let coord_channel = run_loop.coordinator_channels();
+
+    let run_loop_thread = thread::spawn(move || run_loop.start(None, 0));
+    wait_for_runloop(&blocks_processed);
+    boot_to_epoch_3(
+        &naka_conf,
+        &blocks_processed,
+        stacker_sk,
+        &mut btc_regtest_controller,
+    );
+
+    next_block_and(&mut btc_regtest_controller, 60, || {
+        Ok(vrfs_submitted.load(Ordering::SeqCst) >= 1)
+    })
+    .unwrap();
+    next_block_and(&mut btc_regtest_controller, 60, || {
+        Ok(commits_submitted.load(Ordering::SeqCst) >= 1)
+    })
+    .unwrap();
+
+    // mine a couple of healthy tenures first, so there's a real chain to
+    // have a gap appear in
+    for _ in 0..2 {
+        next_block_and_mine_commit(
+            &mut btc_regtest_controller,
+            60,
+            &coord_channel,
+            &commits_submitted,
+        )
+        .unwrap();
+    }
+
+    let burnchain = naka_conf.get_burnchain();
+    let sortdb = burnchain.open_sortition_db(true).unwrap();
+    let (chainstate, _) = StacksChainState::open(
+        naka_conf.is_mainnet(),
+        naka_conf.burnchain.chain_id,
+        &naka_conf.get_chainstate_path_str(),
+        None,
+    )
+    .unwrap();
+
+    let tip_before_stall = NakamotoChainState::get_canonical_block_header(chainstate.db(), &sortdb)
+        .unwrap()
+        .unwrap();
+
+    // stall: the miner still wins sortition, but its commit is suppressed,
+    // so the tenure never produces a block
+    with_commits_skipped(&commits_submitted, || {
+        btc_regtest_controller.build_next_block(1);
+        thread::sleep(Duration::from_secs(5));
+    });
+
+    let tip_during_stall = NakamotoChainState::get_canonical_block_header(chainstate.db(), &sortdb)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tip_before_stall.index_block_hash(),
+        tip_during_stall.index_block_hash(),
+        "tip should not have advanced while commits were suppressed"
+    );
+
+    // run the repair routine against the stalled chainstate
+    let burn_dbconn = btc_regtest_controller.sortdb_ref().index_conn();
+    let repaired_blocks = NakamotoBlockBuilder::repair_nakamoto_chainstate(
+        &chainstate,
+        &burn_dbconn,
+        stacks::chainstate::nakamoto::miner::ShadowRepairMode::Repair,
+    )
+    .expect("Failed to repair stalled chainstate");
+    // `build_next_block(1)` advanced the burnchain by exactly one stalled
+    // sortition, so exactly one shadow block should be synthesized to fill
+    // the gap it left.
+    assert_eq!(
+        repaired_blocks.len(),
+        1,
+        "expected the repair routine to synthesize exactly one shadow block"
+    );
+    assert!(
+        repaired_blocks
+            .iter()
+            .all(|block| is_shadow_block_header(&block.header)),
+        "every synthesized block should be marked as a shadow block in its header"
+    );
+
+    // the shadow block itself -- not the mining that resumes below -- is
+    // what's supposed to reconnect the chain, so check its ancestry before
+    // doing anything else: it must chain directly onto the pre-stall tip
+    // and occupy exactly the gapped height.
+    let repaired_block = &repaired_blocks[0];
+    assert_eq!(
+        repaired_block.header.parent_block_id,
+        tip_before_stall.index_block_hash(),
+        "the shadow block should chain directly onto the pre-stall tip"
+    );
+    assert_eq!(
+        repaired_block.header.chain_length,
+        tip_before_stall.stacks_block_height + 1,
+        "the shadow block should occupy exactly the gapped height"
+    );
+    let repaired_block_id = repaired_block.header.block_id();
+
+    // resume normal mining and confirm the tip now advances *through* the
+    // repaired shadow block, rather than e.g. normal mining independently
+    // skipping past the gap on its own. If the shadow block were never
+    // really inserted into chainstate, the next miner would have nothing
+    // to build on and this call would fail outright.
+    next_block_and_mine_commit(
+        &mut btc_regtest_controller,
+        60,
+        &coord_channel,
+        &commits_submitted,
+    )
+    .unwrap();
+
+    let tip_after_repair = NakamotoChainState::get_canonical_block_header(chainstate.db(), &sortdb)
+        .unwrap()
+        .unwrap();
+    assert!(
+        tip_after_repair.stacks_block_height > tip_before_stall.stacks_block_height,
+        "canonical tip should have advanced past the repaired gap"
+    );
+    let tip_after_repair_header = tip_after_repair
+        .anchored_header
+        .as_stacks_nakamoto()
+        .expect("tip after repair should be a Nakamoto block");
+    assert_eq!(
+        tip_after_repair_header.parent_block_id, repaired_block_id,
+        "the resumed tenure should build directly on the repaired shadow block's own index_block_hash, \
+         proving the shadow block was really inserted rather than just coincidentally mined past"
+    );
+
+    coord_channel
+        .lock()
+        .expect("Mutex poisoned")
+        .stop_chains_coordinator();
+    run_loop_stopper.store(false, Ordering::SeqCst);
+
+    run_loop_thread.join().unwrap();
+}
+
+#[test]
+#[ignore]
+/// Boots to epoch 3 under `burnchain.mode = "nakamoto-testnet"` and asserts
+/// that the resulting `PoxConstants` match
+/// `PoxConstants::nakamoto_testnet_default()`, and that pox-4 is active by
+/// the time the chain crosses that preset's `pox_4_activation_height`.
+fn nakamoto_testnet_pox_constants_line_up() {
+    if env::var("BITCOIND_TEST") != Ok("1".into()) {
+        return;
+    }
+
+    let (mut naka_conf, _miner_account) = naka_testnet_integration_conf(None);
+    let stacker_sk = setup_stacker(&mut naka_conf);
+
+    let mut btcd_controller = BitcoinCoreController::new(naka_conf.clone());
+    btcd_controller
+        .start_bitcoind()
+        .expect("Failed starting bitcoind");
+    let mut btc_regtest_controller = BitcoinRegtestController::new(naka_conf.clone(), None);
+    btc_regtest_controller.bootstrap_chain(201);
+
+    let mut run_loop = boot_nakamoto::BootRunLoop::new(naka_conf.clone()).unwrap();
+    let run_loop_stopper = run_loop.get_termination_switch();
+    let Counters {
+        blocks_processed, ..
+    } = run_loop.counters();
+    let coord_channel = run_loop.coordinator_channels();
+
+    let run_loop_thread = thread::spawn(move || run_loop.start(None, 0));
+    wait_for_runloop(&blocks_processed);
+    boot_to_epoch_3(
+        &naka_conf,
+        &blocks_processed,
+        stacker_sk,
+        &mut btc_regtest_controller,
+    );
+
+    let expected = stacks::burnchains::PoxConstants::nakamoto_testnet_default();
+    let burnchain = naka_conf.get_burnchain();
+    assert_eq!(
+        burnchain.pox_constants.reward_cycle_length, expected.reward_cycle_length,
+        "reward cycle length should come from the nakamoto-testnet preset"
+    );
+    assert_eq!(
+        burnchain.pox_constants.prepare_length, expected.prepare_length,
+        "prepare length should come from the nakamoto-testnet preset"
+    );
+    assert_eq!(
+        burnchain.pox_constants.pox_4_activation_height,
+        expected.pox_4_activation_height
+    );
+
+    let sortdb = burnchain.open_sortition_db(true).unwrap();
+    let tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn()).unwrap();
+    assert!(
+        tip.block_height >= expected.pox_4_activation_height as u64,
+        "test should have run past the preset's pox-4 activation height"
+    );
+
+    coord_channel
+        .lock()
+        .expect("Mutex poisoned")
+        .stop_chains_coordinator();
+    run_loop_stopper.store(false, Ordering::SeqCst);
+
+    run_loop_thread.join().unwrap();
+}
+
+/// Query the active signer set for `reward_cycle` through the node's
+/// `/v3/signer` stackers endpoint, returning the per-signer `signing_key`
+/// of every signer it reports. Used so tests can validate a block proposal
+/// against the real stacked signer set instead of a synthetic one.
+fn query_signer_set(http_origin: &str, reward_cycle: u64) -> Vec<Secp256k1PublicKey> {
+    let client = reqwest::blocking::Client::new();
+    let path = format!("{http_origin}/v3/signer/{reward_cycle}");
+    let response: serde_json::Value = client
+        .get(&path)
+        .send()
+        .expect("Failed to query /v3/signer")
+        .json()
+        .expect("Failed to parse GetSignerResponse");
+
+    response["signers"]
+        .as_array()
+        .expect("GetSignerResponse missing `signers` array")
+        .iter()
+        .map(|entry| {
+            let hex = entry["signing_key"]
+                .as_str()
+                .expect("signer entry missing `signing_key`");
+            Secp256k1PublicKey::from_hex(hex).expect("Failed to parse signer public key")
+        })
+        .collect()
+}
+
 /// Test `/v2/block_proposal` API endpoint
 ///
 /// This endpoint allows miners to propose Nakamoto blocks to a node,
 /// and test if they would be accepted or rejected
-///
-/// Notes:
-/// - The `tenure_start_block` supplied doesn't seem to matter. It is required by `NakamotoBlockBuilder` but not used/checked?
 #[test]
 #[ignore]
 fn block_proposal_api_endpoint() {
@@ -532,6 +1263,8 @@ fn block_proposal_api_endpoint() {
     let (mut conf, _miner_account) = naka_neon_integration_conf(None);
     let account_keys = add_initial_balances(&mut conf, 10, 1000000);
     let stacker_sk = setup_stacker(&mut conf);
+    let block_proposal_token = "test-block-proposal-token".to_string();
+    conf.node.block_proposal_token = Some(block_proposal_token.clone());
 
     test_observer::spawn();
     let observer_port = test_observer::EVENT_OBSERVER_PORT;
@@ -615,8 +1348,35 @@ fn block_proposal_api_endpoint() {
     // TODO (hack) instantiate the sortdb in the burnchain
     _ = btc_regtest_controller.sortdb_mut();
 
+    // Query the actual stacked signer set for the current reward cycle
+    // through `/v3/signer`, instead of assuming our synthetic `SelfSigner`
+    // is the only signer that matters. `stacker_sk` is funded in
+    // `setup_stacker` and stacks in `boot_to_epoch_3`, so the current
+    // reward cycle should have a non-empty signer set.
+    let http_origin = format!("http://{}", &conf.node.rpc_bind);
+    let reward_cycle = burnchain.block_height_to_reward_cycle(
+        btc_regtest_controller.get_headers_height(),
+    )
+    .expect("Chain height is not in a reward cycle");
+    let signer_set = query_signer_set(&http_origin, reward_cycle);
+    assert!(
+        !signer_set.is_empty(),
+        "Expected at least one stacked signer for the current reward cycle"
+    );
+
     // Set up test signer
     let signer = conf.miner.self_signing_key.as_mut().unwrap();
+    // `/v3/signer`'s `signing_key` entries are each signer's own per-signer
+    // public key, not the reward cycle's aggregate key -- comparing those
+    // against `aggregate_public_key()` only happens to work here because a
+    // `SelfSigner` simulates the entire signer set by itself, so its own
+    // signing key and its "aggregate" are the same value. Use
+    // `signing_public_key()` (the per-signer key) so this assertion stays
+    // correct if this test is ever extended to a real multi-signer set.
+    assert!(
+        signer_set.contains(&signer.signing_public_key()),
+        "Our self-signing key should be a member of the queried signer set"
+    );
 
     // ----- Setup boilerplate finished, test block proposal API endpoint -----
 
@@ -707,16 +1467,23 @@ fn block_proposal_api_endpoint() {
         block,
         tenure_start_block: parent_block_id,
         chain_id: chainstate.chain_id,
+        burn_height: snapshot.block_height,
+        reward_cycle: burnchain
+            .block_height_to_reward_cycle(snapshot.block_height)
+            .expect("Snapshot height is not in a reward cycle"),
     };
 
     const HTTP_ACCEPTED: u16 = 202;
     const HTTP_BADREQUEST: u16 = 400;
-    // TODO: Check error codes?
+    const HTTP_UNAUTHORIZED: u16 = 401;
+    let correct_auth = Some(format!("Bearer {block_proposal_token}"));
     let test_cases = [
         (
             "Valid Nakamoto block proposal",
             sign(proposal.clone()),
+            correct_auth.clone(),
             HTTP_ACCEPTED,
+            None,
         ),
         (
             "Corrupted (bit flipped after signing)",
@@ -725,17 +1492,53 @@ fn block_proposal_api_endpoint() {
                 sp.block.header.consensus_hash.0[3] ^= 0x07;
                 sp
             })(),
+            correct_auth.clone(),
+            HTTP_BADREQUEST,
+            Some(ValidateRejectCode::BadBlockHash),
+        ),
+        (
+            // signed with a key that never stacked, so it's not a member of
+            // the queried signer set -- should be rejected the same way
+            // the corrupted proposal above is
+            "Signed by an out-of-set key",
+            (|| {
+                let mut p = proposal.clone();
+                p.block
+                    .header
+                    .sign_miner(&privk)
+                    .expect("Miner failed to sign");
+                let mut outsider = SelfSigner::from_seed(0xDEAD);
+                outsider.sign_nakamoto_block(&mut p.block);
+                p
+            })(),
+            correct_auth.clone(),
             HTTP_BADREQUEST,
+            None,
         ),
         (
-            // FIXME: Why does `NakamotoBlockBuilder` not check this?
             "Invalid `tenure_start_block`",
             (|| {
                 let mut p = proposal.clone();
                 p.tenure_start_block.0[8] ^= 0x55;
                 sign(p)
             })(),
-            HTTP_ACCEPTED,
+            correct_auth.clone(),
+            HTTP_BADREQUEST,
+            None,
+        ),
+        (
+            "Missing Authorization header",
+            sign(proposal.clone()),
+            None,
+            HTTP_UNAUTHORIZED,
+            None,
+        ),
+        (
+            "Wrong Authorization token",
+            sign(proposal.clone()),
+            Some("Bearer not-the-right-token".to_string()),
+            HTTP_UNAUTHORIZED,
+            None,
         ),
     ];
 
@@ -748,22 +1551,54 @@ fn block_proposal_api_endpoint() {
     let http_origin = format!("http://{}", &conf.node.rpc_bind);
     let path = format!("{http_origin}/v2/block_proposal");
 
-    for (test_description, block_proposal, expected_response) in test_cases {
+    for (test_description, block_proposal, auth_header, expected_response, expected_reason_code) in
+        test_cases
+    {
         eprintln!("test_block_proposal(): {test_description}");
         eprintln!("{block_proposal:?}");
 
         // Send POST request
-        let response = client
+        let mut req = client
             .post(&path)
             .header("Content-Type", "application/json")
-            .json(&block_proposal)
-            .send()
-            .expect("Failed to POST");
+            .json(&block_proposal);
+        if let Some(auth_header) = auth_header {
+            req = req.header("Authorization", auth_header);
+        }
+        let response = req.send().expect("Failed to POST");
 
         eprintln!("{response:?}");
         assert_eq!(response.status().as_u16(), expected_response);
+
+        if let Some(expected_reason_code) = expected_reason_code {
+            let body: serde_json::Value = response.json().expect("Failed to parse response JSON");
+            let reason_code: ValidateRejectCode =
+                serde_json::from_value(body["reason_code"].clone())
+                    .expect("Response missing `reason_code`");
+            assert_eq!(
+                reason_code, expected_reason_code,
+                "unexpected reason_code for: {test_description}"
+            );
+        }
     }
 
+    // Submit the same valid proposal consensus-serialized, as
+    // `application/octet-stream`, and expect the same acceptance
+    eprintln!("test_block_proposal(): Valid Nakamoto block proposal (binary)");
+    let binary_proposal = sign(proposal.clone());
+    let mut encoded = vec![];
+    binary_proposal
+        .consensus_serialize(&mut encoded)
+        .expect("Failed to consensus-serialize block proposal");
+    let response = client
+        .post(&path)
+        .header("Content-Type", "application/octet-stream")
+        .header("Authorization", correct_auth.clone().unwrap())
+        .body(encoded)
+        .send()
+        .expect("Failed to POST binary proposal");
+    assert_eq!(response.status().as_u16(), HTTP_ACCEPTED);
+
     // Clean up
     coord_channel
         .lock()